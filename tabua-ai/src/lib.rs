@@ -0,0 +1,3 @@
+pub mod mcts;
+pub mod minimax;
+pub mod transposition;
@@ -0,0 +1,449 @@
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tabua_engine::{ActivePlayers, FromPublicState, Outcome};
+
+struct Node<State, PlayerId, Action> {
+    state: State,
+    /// Player who played the action leading here, rewards are from their point of view
+    player: Option<PlayerId>,
+    action: Option<Action>,
+    /// Player to move, `None` once the game is over
+    mover: Option<PlayerId>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Action>,
+    visits: u32,
+    reward: f64,
+}
+
+/// Monte Carlo Tree Search player using the UCT selection rule.
+///
+/// Only needs legal actions and a terminal [`Outcome`], so it works for games
+/// without a good evaluation function and for any number of players. Each
+/// node keeps the rewards of the player who moved into it, which is what its
+/// parent maximizes when selecting.
+pub struct Mcts {
+    /// `None` only together with a time budget, so the search always ends
+    iterations: Option<usize>,
+    time_budget: Option<Duration>,
+    max_rollout_depth: usize,
+    exploration: f64,
+    seed: u64,
+}
+
+impl Mcts {
+    pub fn new() -> Self {
+        Self {
+            iterations: Some(1000),
+            time_budget: None,
+            max_rollout_depth: 1000,
+            exploration: std::f64::consts::SQRT_2,
+            seed: 0,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    /// Search until `time_budget` is spent, combined with the iteration limit if any
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// Search until `time_budget` is spent, without any iteration limit
+    pub fn with_time_budget_only(mut self, time_budget: Duration) -> Self {
+        self.iterations = None;
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// Stop random playouts after `max_rollout_depth` actions, so games that
+    /// may never end can still be searched. A playout cut short counts as half
+    /// a win for every player. Defaults to 1000.
+    pub fn with_max_rollout_depth(mut self, max_rollout_depth: usize) -> Self {
+        self.max_rollout_depth = max_rollout_depth;
+        self
+    }
+
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+
+    /// Seed of the random number generator, the same seed and budget give the same result
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Most visited action for the player to move, `None` if there is nothing to play.
+    ///
+    /// At least one iteration runs, so a zero or spent budget still gives an action.
+    pub async fn best_action<'a, E>(&self, engine: &E) -> Result<Option<E::Action>, E::Error>
+    where
+        E: FromPublicState<'a>,
+        E::PublicState: Clone,
+        E::PlayerId: Clone,
+        E::Action: Clone,
+        E::EndGame: Outcome<E::PlayerId>,
+        E::CurrentPlayers: ActivePlayers<E::PlayerId>,
+    {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let deadline = self.time_budget.map(|budget| Instant::now() + budget);
+
+        let (mover, untried) = Self::moves(engine).await?;
+        if untried.is_empty() {
+            return Ok(None);
+        }
+
+        let mut nodes = vec![Node {
+            state: engine.public_state().await?.clone(),
+            player: None,
+            action: None,
+            mover,
+            parent: None,
+            children: vec![],
+            untried,
+            visits: 0,
+            reward: 0.0,
+        }];
+
+        let mut iteration = 0;
+        while iteration == 0
+            || (self.iterations.is_none_or(|limit| iteration < limit)
+                && deadline.is_none_or(|deadline| Instant::now() < deadline))
+        {
+            iteration += 1;
+
+            // Selection
+            let mut current = 0;
+            while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+                current = self.select(&nodes, current);
+            }
+
+            // Expansion
+            if !nodes[current].untried.is_empty() {
+                let index = rng.gen_range(0..nodes[current].untried.len());
+                let action = nodes[current].untried.swap_remove(index);
+
+                let mut child = E::from_public_state(nodes[current].state.clone());
+                child.apply_action(action.clone()).await?;
+                let (mover, untried) = Self::moves(&child).await?;
+
+                let id = nodes.len();
+                nodes.push(Node {
+                    state: child.public_state().await?.clone(),
+                    player: nodes[current].mover.clone(),
+                    action: Some(action),
+                    mover,
+                    parent: Some(current),
+                    children: vec![],
+                    untried,
+                    visits: 0,
+                    reward: 0.0,
+                });
+                nodes[current].children.push(id);
+                current = id;
+            }
+
+            // Simulation
+            let mut rollout = E::from_public_state(nodes[current].state.clone());
+            for _ in 0..self.max_rollout_depth {
+                let (_, mut actions) = Self::moves(&rollout).await?;
+                if actions.is_empty() {
+                    break;
+                }
+
+                let action = actions.swap_remove(rng.gen_range(0..actions.len()));
+                rollout.apply_action(action).await?;
+            }
+            let outcome = rollout.results().await?;
+
+            // Backpropagation
+            let mut node = Some(current);
+            while let Some(id) = node {
+                let node_ref = &mut nodes[id];
+                node_ref.visits += 1;
+                if let Some(player) = &node_ref.player {
+                    node_ref.reward += if outcome.is_over() {
+                        outcome.reward(player)
+                    } else {
+                        0.5
+                    };
+                }
+                node = node_ref.parent;
+            }
+        }
+
+        Ok(nodes[0]
+            .children
+            .iter()
+            .max_by_key(|child| nodes[**child].visits)
+            .and_then(|child| nodes[*child].action.clone()))
+    }
+
+    fn select<State, PlayerId, Action>(
+        &self,
+        nodes: &[Node<State, PlayerId, Action>],
+        parent: usize,
+    ) -> usize {
+        let log_visits = f64::from(nodes[parent].visits).ln();
+        let uct = |child: usize| {
+            let node = &nodes[child];
+            let visits = f64::from(node.visits);
+            node.reward / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+
+        *nodes[parent]
+            .children
+            .iter()
+            .max_by(|a, b| uct(**a).total_cmp(&uct(**b)))
+            .expect("selection only happens on expanded nodes")
+    }
+
+    /// Player to move and its legal actions, nothing once the game is over
+    async fn moves<'a, E>(engine: &E) -> Result<(Option<E::PlayerId>, Vec<E::Action>), E::Error>
+    where
+        E: FromPublicState<'a>,
+        E::EndGame: Outcome<E::PlayerId>,
+        E::CurrentPlayers: ActivePlayers<E::PlayerId>,
+    {
+        if engine.results().await?.is_over() {
+            return Ok((None, vec![]));
+        }
+
+        let Some(player) = engine
+            .current_players()
+            .await?
+            .active_players()
+            .into_iter()
+            .next()
+        else {
+            return Ok((None, vec![]));
+        };
+
+        let actions = engine.legal_actions(&player).await?;
+        Ok((Some(player), actions))
+    }
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use serde::{Deserialize, Serialize};
+    use tabua_engine::Engine;
+    use tictactoe::{Action, PlayerId, Position, TicTacToeEngine, TicTacToeState};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone)]
+    struct Pile {
+        tokens: u32,
+        players: u32,
+        turn: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Winner(Option<u32>);
+
+    impl Outcome<u32> for Winner {
+        fn is_over(&self) -> bool {
+            self.0.is_some()
+        }
+
+        fn reward(&self, player: &u32) -> f64 {
+            if self.0 == Some(*player) {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    /// Players take turns removing one or two tokens, whoever takes the last one wins
+    struct PileEngine {
+        state: Pile,
+    }
+
+    #[async_trait]
+    impl Engine<'_> for PileEngine {
+        type PublicState = Pile;
+        type PrivateState = ();
+        type PlayerId = u32;
+        type Action = u32;
+        type EndGame = Winner;
+        type Error = ();
+
+        async fn public_state(&self) -> Result<&Pile, ()> {
+            Ok(&self.state)
+        }
+        async fn private_state(&self, _user: &u32) -> Result<Vec<()>, ()> {
+            Ok(vec![])
+        }
+
+        async fn validate_action(&self, action: &u32) -> Result<(), ()> {
+            match action {
+                1 | 2 if *action <= self.state.tokens => Ok(()),
+                _ => Err(()),
+            }
+        }
+        async fn legal_actions(&self, player: &u32) -> Result<Vec<u32>, ()> {
+            if *player != self.state.turn {
+                return Ok(vec![]);
+            }
+
+            Ok((1..=self.state.tokens.min(2)).collect())
+        }
+        async fn apply_action(&mut self, action: u32) -> Result<(), ()> {
+            self.validate_action(&action).await?;
+            let state = &mut self.state;
+            state.tokens -= action;
+            if state.tokens > 0 {
+                state.turn = (state.turn + 1) % state.players;
+            }
+            Ok(())
+        }
+
+        async fn current_players(&self) -> Result<Vec<u32>, ()> {
+            Ok(vec![self.state.turn])
+        }
+
+        async fn results(&self) -> Result<Winner, ()> {
+            Ok(Winner((self.state.tokens == 0).then_some(self.state.turn)))
+        }
+    }
+
+    impl FromPublicState<'_> for PileEngine {
+        fn from_public_state(state: Pile) -> Self {
+            Self { state }
+        }
+    }
+
+    async fn play(moves: &[(usize, usize)]) -> TicTacToeEngine {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        for (row, column) in moves {
+            let player_id = engine.current_players().await.unwrap();
+            engine
+                .apply_action(Action::MarkBoard {
+                    player_id,
+                    pos: Position::new(*row, *column),
+                })
+                .await
+                .unwrap();
+        }
+
+        engine
+    }
+
+    fn position(action: &Action) -> (usize, usize) {
+        let Action::MarkBoard { pos, .. } = action;
+        (pos.row, pos.column)
+    }
+
+    #[tokio::test]
+    async fn takes_the_win() {
+        let engine = play(&[(0, 0), (1, 0), (0, 1), (1, 1)]).await;
+        let action = Mcts::new().best_action(&engine).await.unwrap().unwrap();
+
+        assert_eq!(position(&action), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn blocks_the_loss() {
+        let engine = play(&[(0, 0), (1, 1), (0, 1)]).await;
+        let action = Mcts::new()
+            .with_iterations(3000)
+            .best_action(&engine)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(position(&action), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn three_players() {
+        let engine = PileEngine::from_public_state(Pile {
+            tokens: 2,
+            players: 3,
+            turn: 1,
+        });
+
+        assert_eq!(Mcts::new().best_action(&engine).await.unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn reproducible_with_seed() {
+        let engine = play(&[(1, 1)]).await;
+        let mcts = Mcts::new().with_iterations(200).with_seed(42);
+
+        let first = mcts.best_action(&engine).await.unwrap();
+        for _ in 0..3 {
+            assert_eq!(mcts.best_action(&engine).await.unwrap(), first);
+        }
+    }
+
+    #[tokio::test]
+    async fn nothing_to_play() {
+        let engine = play(&[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]).await;
+
+        assert_eq!(Mcts::new().best_action(&engine).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn time_budget() {
+        let engine = TicTacToeEngine::new(TicTacToeState::new(5, 4).unwrap());
+        let action = Mcts::new()
+            .with_time_budget_only(Duration::from_millis(50))
+            .best_action(&engine)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            action,
+            Some(Action::MarkBoard {
+                player_id: PlayerId::Cross,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn empty_budget() {
+        let engine = play(&[(1, 1)]).await;
+
+        let action = Mcts::new().with_iterations(0).best_action(&engine).await;
+        assert!(action.unwrap().is_some());
+
+        let mcts = Mcts::new().with_time_budget_only(Duration::ZERO);
+        assert!(mcts.best_action(&engine).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rollout_depth() {
+        let engine = PileEngine::from_public_state(Pile {
+            tokens: u32::MAX,
+            players: 2,
+            turn: 0,
+        });
+        let action = Mcts::new()
+            .with_iterations(50)
+            .with_max_rollout_depth(10)
+            .best_action(&engine)
+            .await
+            .unwrap();
+
+        assert!(matches!(action, Some(1 | 2)));
+    }
+}
@@ -0,0 +1,507 @@
+use std::time::{Duration, Instant};
+
+use tabua_engine::{ActivePlayers, FromPublicState, Outcome};
+
+use crate::transposition::{Bound, Entry, TranspositionTable};
+
+/// Score of a won game, evaluation functions must stay well below it
+pub const WIN: i64 = 1 << 40;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SearchResult<Action> {
+    pub action: Action,
+    pub score: i64,
+    pub depth: usize,
+}
+
+/// Depth limited minimax search with alpha-beta pruning and iterative deepening.
+///
+/// Positions are explored by rebuilding engines from cloned public states.
+/// Finished games are scored from [`Outcome::reward`], positions where the
+/// depth limit is reached are scored by `evaluation` for the searching player.
+pub struct Minimax<F, H = NoHasher> {
+    evaluation: F,
+    max_depth: usize,
+    time_budget: Option<Duration>,
+    hasher: H,
+    table_size: usize,
+}
+
+/// Identifies positions for the transposition table of [`Minimax`]
+pub trait StateHasher<State> {
+    fn hash_state(&self, state: &State) -> Option<u64>;
+}
+
+/// Searches without transposition table
+pub struct NoHasher;
+
+impl<State> StateHasher<State> for NoHasher {
+    fn hash_state(&self, _state: &State) -> Option<u64> {
+        None
+    }
+}
+
+impl<State, H> StateHasher<State> for H
+where
+    H: Fn(&State) -> u64,
+{
+    fn hash_state(&self, state: &State) -> Option<u64> {
+        Some(self(state))
+    }
+}
+
+/// Depth stored for positions searched to the end of the game
+const SOLVED: usize = usize::MAX;
+
+struct Search<'p, PlayerId, Action> {
+    root: &'p PlayerId,
+    deadline: Option<Instant>,
+    depth_limited: bool,
+    timed_out: bool,
+    table: TranspositionTable<Action>,
+}
+
+impl<PlayerId, Action> Search<'_, PlayerId, Action> {
+    fn out_of_time(&mut self) -> bool {
+        if let Some(deadline) = self.deadline {
+            self.timed_out |= Instant::now() >= deadline;
+        }
+
+        self.timed_out
+    }
+}
+
+fn terminal_score(reward: f64, ply: usize) -> i64 {
+    // Prefer quick wins and slow losses
+    let scale = WIN - ply as i64;
+    (scale as f64 * (2.0 * reward - 1.0)) as i64
+}
+
+// Terminal scores depend on the ply they are found at, the table stores
+// them relative to the position instead
+fn to_table(score: i64, ply: usize) -> i64 {
+    if score.abs() > WIN / 2 {
+        score + score.signum() * ply as i64
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i64, ply: usize) -> i64 {
+    if score.abs() > WIN / 2 {
+        score - score.signum() * ply as i64
+    } else {
+        score
+    }
+}
+
+impl<F> Minimax<F> {
+    pub fn new(evaluation: F) -> Self {
+        Self {
+            evaluation,
+            max_depth: usize::MAX,
+            time_budget: None,
+            hasher: NoHasher,
+            table_size: 0,
+        }
+    }
+}
+
+impl<F, H> Minimax<F, H> {
+    /// Remember positions reached through different move orders,
+    /// `hasher` must tell apart every position that can be scored differently
+    pub fn with_transposition_table<G>(self, hasher: G, table_size: usize) -> Minimax<F, G> {
+        Minimax {
+            evaluation: self.evaluation,
+            max_depth: self.max_depth,
+            time_budget: self.time_budget,
+            hasher,
+            table_size,
+        }
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Stop deepening once `time_budget` is spent, keeping the deepest complete result
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    /// Best action for the player to move, `None` if there is nothing to play
+    pub async fn best_action<'a, E>(
+        &self,
+        engine: &E,
+    ) -> Result<Option<SearchResult<E::Action>>, E::Error>
+    where
+        E: FromPublicState<'a>,
+        E::PublicState: Clone,
+        E::PlayerId: PartialEq,
+        E::Action: Clone + PartialEq,
+        E::EndGame: Outcome<E::PlayerId>,
+        E::CurrentPlayers: ActivePlayers<E::PlayerId>,
+        F: Fn(&E::PublicState, &E::PlayerId) -> i64,
+        H: StateHasher<E::PublicState>,
+    {
+        if engine.results().await?.is_over() {
+            return Ok(None);
+        }
+
+        let players = engine.current_players().await?.active_players();
+        let Some(root) = players.first() else {
+            return Ok(None);
+        };
+
+        let mut actions = engine.legal_actions(root).await?;
+        let state = engine.public_state().await?.clone();
+
+        let mut search = Search {
+            root,
+            deadline: self.time_budget.map(|budget| Instant::now() + budget),
+            depth_limited: false,
+            timed_out: false,
+            table: TranspositionTable::new(self.table_size),
+        };
+
+        let mut best: Option<SearchResult<E::Action>> = None;
+        for depth in 1..=self.max_depth {
+            search.depth_limited = false;
+
+            let mut alpha = i64::MIN;
+            let mut current: Option<SearchResult<E::Action>> = None;
+            for action in &actions {
+                let mut child = E::from_public_state(state.clone());
+                child.apply_action(action.clone()).await?;
+
+                let score = self
+                    .search(child, depth - 1, 1, alpha, i64::MAX, &mut search)
+                    .await?;
+                if search.timed_out {
+                    break;
+                }
+
+                if current.as_ref().is_none_or(|current| score > current.score) {
+                    alpha = alpha.max(score);
+                    current = Some(SearchResult {
+                        action: action.clone(),
+                        score,
+                        depth,
+                    });
+                }
+            }
+
+            if search.timed_out {
+                best = best.or(current);
+                break;
+            }
+
+            // Explore the best action first on the next iteration
+            if let Some(current) = &current {
+                if let Some(index) = actions.iter().position(|a| *a == current.action) {
+                    let action = actions.remove(index);
+                    actions.insert(0, action);
+                }
+            }
+
+            best = current;
+            if !search.depth_limited {
+                break;
+            }
+        }
+
+        Ok(best)
+    }
+
+    async fn search<'a, E>(
+        &self,
+        engine: E,
+        depth: usize,
+        ply: usize,
+        mut alpha: i64,
+        mut beta: i64,
+        search: &mut Search<'_, E::PlayerId, E::Action>,
+    ) -> Result<i64, E::Error>
+    where
+        E: FromPublicState<'a>,
+        E::PublicState: Clone,
+        E::PlayerId: PartialEq,
+        E::Action: Clone + PartialEq,
+        E::EndGame: Outcome<E::PlayerId>,
+        E::CurrentPlayers: ActivePlayers<E::PlayerId>,
+        F: Fn(&E::PublicState, &E::PlayerId) -> i64,
+        H: StateHasher<E::PublicState>,
+    {
+        if search.out_of_time() {
+            return Ok(0);
+        }
+
+        let outcome = engine.results().await?;
+        if outcome.is_over() {
+            return Ok(terminal_score(outcome.reward(search.root), ply));
+        }
+
+        let state = engine.public_state().await?;
+        if depth == 0 {
+            search.depth_limited = true;
+            return Ok((self.evaluation)(state, search.root));
+        }
+
+        let players = engine.current_players().await?.active_players();
+        let Some(player) = players.first() else {
+            return Ok((self.evaluation)(state, search.root));
+        };
+
+        let mut actions = engine.legal_actions(player).await?;
+        if actions.is_empty() {
+            return Ok((self.evaluation)(state, search.root));
+        }
+
+        let key = self.hasher.hash_state(state);
+        if let Some(entry) = key.and_then(|key| search.table.get(key)) {
+            if entry.depth >= depth {
+                search.depth_limited |= entry.depth != SOLVED;
+
+                let score = from_table(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return Ok(score),
+                    Bound::Lower => alpha = alpha.max(score),
+                    Bound::Upper => beta = beta.min(score),
+                }
+                if alpha >= beta {
+                    return Ok(score);
+                }
+            }
+
+            // Explore the best action of the previous search first
+            if let Some(index) = entry
+                .best_action
+                .as_ref()
+                .and_then(|best| actions.iter().position(|a| a == best))
+            {
+                let action = actions.remove(index);
+                actions.insert(0, action);
+            }
+        }
+
+        let (alpha_start, beta_start) = (alpha, beta);
+        let outer_depth_limited = std::mem::take(&mut search.depth_limited);
+
+        let maximizing = player == search.root;
+        let mut best = if maximizing { i64::MIN } else { i64::MAX };
+        let mut best_action = None;
+        for action in actions {
+            let mut child = E::from_public_state(state.clone());
+            child.apply_action(action.clone()).await?;
+
+            let score =
+                Box::pin(self.search(child, depth - 1, ply + 1, alpha, beta, search)).await?;
+            if search.timed_out {
+                return Ok(0);
+            }
+
+            if (maximizing && score > best) || (!maximizing && score < best) {
+                best = score;
+                best_action = Some(action);
+            }
+
+            if maximizing {
+                alpha = alpha.max(best);
+            } else {
+                beta = beta.min(best);
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let depth_limited = search.depth_limited;
+        search.depth_limited |= outer_depth_limited;
+
+        if let Some(key) = key {
+            let bound = if best <= alpha_start {
+                Bound::Upper
+            } else if best >= beta_start {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            search.table.store(Entry {
+                key,
+                depth: if depth_limited { depth } else { SOLVED },
+                score: to_table(best, ply),
+                bound,
+                best_action,
+            });
+        }
+
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tabua_engine::Engine;
+    use tabua_utils::board::zobrist::Zobrist;
+    use tictactoe::{
+        Action, CellState, EndGameState, PlayerId, Position, TicTacToeEngine, TicTacToeState,
+    };
+
+    use super::*;
+
+    async fn play(state: TicTacToeState, moves: &[(usize, usize)]) -> TicTacToeEngine {
+        let mut engine = TicTacToeEngine::new(state);
+        for (row, column) in moves {
+            let player_id = engine.current_players().await.unwrap();
+            engine
+                .apply_action(Action::MarkBoard {
+                    player_id,
+                    pos: Position::new(*row, *column),
+                })
+                .await
+                .unwrap();
+        }
+
+        engine
+    }
+
+    fn position(action: &Action) -> (usize, usize) {
+        let Action::MarkBoard { pos, .. } = action;
+        (pos.row, pos.column)
+    }
+
+    #[tokio::test]
+    async fn takes_the_win() {
+        let engine = play(TicTacToeState::default(), &[(0, 0), (1, 0), (0, 1), (1, 1)]).await;
+        let result = Minimax::new(|_: &_, _: &_| 0)
+            .best_action(&engine)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(position(&result.action), (0, 2));
+        assert!(result.score > 0);
+    }
+
+    #[tokio::test]
+    async fn blocks_the_loss() {
+        let engine = play(TicTacToeState::default(), &[(0, 0), (1, 1), (0, 1)]).await;
+        let result = Minimax::new(|_: &_, _: &_| 0)
+            .best_action(&engine)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(position(&result.action), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn perfect_play_ties() {
+        let minimax = Minimax::new(|_: &_, _: &_| 0);
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+
+        while let Some(result) = minimax.best_action(&engine).await.unwrap() {
+            engine.apply_action(result.action).await.unwrap();
+        }
+
+        assert_eq!(engine.results().await.unwrap(), EndGameState::Tie);
+    }
+
+    fn hasher(size: usize) -> impl Fn(&TicTacToeState) -> u64 {
+        let zobrist = Zobrist::new(size, size, 2, 0);
+        move |state: &TicTacToeState| {
+            zobrist.hash(state.board(), |cell| match cell {
+                CellState::Empty => None,
+                CellState::Cross => Some(0),
+                CellState::Circle => Some(1),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn transposition_table_keeps_scores() {
+        let plain = Minimax::new(|_: &_, _: &_| 0);
+        let cached = Minimax::new(|_: &_, _: &_| 0).with_transposition_table(hasher(3), 1 << 12);
+
+        for moves in [
+            &[][..],
+            &[(1, 1)],
+            &[(0, 0), (1, 1), (2, 2)],
+            &[(0, 1), (0, 0)],
+        ] {
+            let engine = play(TicTacToeState::default(), moves).await;
+            let expected = plain.best_action(&engine).await.unwrap().unwrap();
+            let result = cached.best_action(&engine).await.unwrap().unwrap();
+
+            assert_eq!(result.score, expected.score);
+            assert_eq!(result.depth, expected.depth);
+        }
+    }
+
+    #[tokio::test]
+    async fn transposition_table_perfect_play() {
+        let minimax = Minimax::new(|_: &_, _: &_| 0).with_transposition_table(hasher(3), 1 << 12);
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+
+        while let Some(result) = minimax.best_action(&engine).await.unwrap() {
+            engine.apply_action(result.action).await.unwrap();
+        }
+
+        assert_eq!(engine.results().await.unwrap(), EndGameState::Tie);
+    }
+
+    #[tokio::test]
+    async fn nothing_to_play() {
+        let engine = play(
+            TicTacToeState::default(),
+            &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)],
+        )
+        .await;
+
+        let result = Minimax::new(|_: &_, _: &_| 0)
+            .best_action(&engine)
+            .await
+            .unwrap();
+        assert_eq!(result, None);
+    }
+
+    #[tokio::test]
+    async fn heuristic_on_large_board() {
+        let engine = play(
+            TicTacToeState::new(6, 4).unwrap(),
+            &[(2, 1), (0, 0), (2, 2), (0, 5)],
+        )
+        .await;
+
+        let result =
+            Minimax::new(|state: &TicTacToeState, player: &PlayerId| state.heuristic(*player))
+                .with_max_depth(2)
+                .best_action(&engine)
+                .await
+                .unwrap()
+                .unwrap();
+
+        // Extending the open three-to-be is the only sensible move
+        assert!(matches!(position(&result.action), (2, 0) | (2, 3)));
+        assert_eq!(result.depth, 2);
+        const { assert!(tictactoe::MAX_HEURISTIC < WIN / 2) };
+    }
+
+    #[tokio::test]
+    async fn time_budget() {
+        let engine = TicTacToeEngine::new(TicTacToeState::new(5, 4).unwrap());
+
+        let result =
+            Minimax::new(|state: &TicTacToeState, player: &PlayerId| state.heuristic(*player))
+                .with_time_budget(Duration::from_millis(50))
+                .best_action(&engine)
+                .await
+                .unwrap();
+
+        assert!(result.is_some());
+    }
+}
@@ -0,0 +1,128 @@
+/// How a stored score relates to the real score of the position
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    Exact,
+    /// The search failed high, the real score is at least the stored one
+    Lower,
+    /// The search failed low, the real score is at most the stored one
+    Upper,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Entry<Action> {
+    pub key: u64,
+    pub depth: usize,
+    pub score: i64,
+    pub bound: Bound,
+    pub best_action: Option<Action>,
+}
+
+/// Fixed size table of search results indexed by position hash.
+///
+/// Each hash maps to a single slot, a new entry replaces the one in its slot
+/// unless it is the same position searched less deeply.
+#[derive(Debug, Clone)]
+pub struct TranspositionTable<Action> {
+    slots: Vec<Option<Entry<Action>>>,
+    len: usize,
+}
+
+impl<Action> TranspositionTable<Action> {
+    /// Table with at least `capacity` slots, rounded up to a power of two
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1).next_power_of_two();
+        Self {
+            slots: std::iter::repeat_with(|| None).take(capacity).collect(),
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key as usize) & (self.slots.len() - 1)
+    }
+
+    pub fn get(&self, key: u64) -> Option<&Entry<Action>> {
+        self.slots[self.index(key)]
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    pub fn store(&mut self, entry: Entry<Action>) {
+        let index = self.index(entry.key);
+        let slot = &mut self.slots[index];
+        match slot {
+            Some(current) if current.key == entry.key && current.depth > entry.depth => {}
+            Some(_) => *slot = Some(entry),
+            None => {
+                *slot = Some(entry);
+                self.len += 1;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(key: u64, depth: usize, score: i64) -> Entry<u8> {
+        Entry {
+            key,
+            depth,
+            score,
+            bound: Bound::Exact,
+            best_action: None,
+        }
+    }
+
+    #[test]
+    fn store_and_get() {
+        let mut table = TranspositionTable::new(6);
+        assert_eq!(table.capacity(), 8);
+        assert!(table.is_empty());
+
+        table.store(entry(3, 2, 10));
+        assert_eq!(table.get(3), Some(&entry(3, 2, 10)));
+        assert_eq!(table.get(11), None);
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn replacement() {
+        let mut table = TranspositionTable::new(8);
+
+        table.store(entry(3, 4, 10));
+        table.store(entry(3, 2, 20));
+        assert_eq!(table.get(3).unwrap().score, 10);
+
+        table.store(entry(3, 4, 30));
+        assert_eq!(table.get(3).unwrap().score, 30);
+
+        // same slot, other position
+        table.store(entry(11, 1, 40));
+        assert_eq!(table.get(3), None);
+        assert_eq!(table.get(11).unwrap().score, 40);
+        assert_eq!(table.len(), 1);
+
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.get(11), None);
+    }
+}
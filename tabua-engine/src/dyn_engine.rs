@@ -0,0 +1,140 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::Engine;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid JSON payload: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Engine error: {0}")]
+    Engine(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Object safe view of an [`Engine`] speaking JSON.
+///
+/// Every engine whose types are owned serde types implements it, so
+/// different games can be hosted together as `Box<dyn DynEngine>`. Methods
+/// share their names with [`Engine`], so call them through the trait object.
+#[async_trait]
+pub trait DynEngine: Send + Sync {
+    async fn public_state(&self) -> Result<Value>;
+    async fn private_state(&self, user: Value) -> Result<Value>;
+
+    async fn validate_action(&self, action: Value) -> Result<()>;
+    async fn legal_actions(&self, player: Value) -> Result<Value>;
+    async fn apply_action(&mut self, action: Value) -> Result<()>;
+
+    async fn current_players(&self) -> Result<Value>;
+
+    async fn results(&self) -> Result<Value>;
+}
+
+fn engine_error(err: impl Display) -> Error {
+    Error::Engine(err.to_string())
+}
+
+#[async_trait]
+impl<E> DynEngine for E
+where
+    E: for<'de> Engine<'de> + Send + Sync,
+    for<'de> <E as Engine<'de>>::PlayerId: DeserializeOwned + Send,
+    for<'de> <E as Engine<'de>>::Action: DeserializeOwned + Send,
+    for<'de> <E as Engine<'de>>::CurrentPlayers: Serialize,
+    for<'de> <E as Engine<'de>>::Error: Display,
+{
+    async fn public_state(&self) -> Result<Value> {
+        let state = <E as Engine<'static>>::public_state(self)
+            .await
+            .map_err(engine_error)?;
+        Ok(serde_json::to_value(state)?)
+    }
+    async fn private_state(&self, user: Value) -> Result<Value> {
+        let user: <E as Engine<'static>>::PlayerId = serde_json::from_value(user)?;
+        let state = <E as Engine<'static>>::private_state(self, &user)
+            .await
+            .map_err(engine_error)?;
+        Ok(serde_json::to_value(state)?)
+    }
+
+    async fn validate_action(&self, action: Value) -> Result<()> {
+        let action: <E as Engine<'static>>::Action = serde_json::from_value(action)?;
+        <E as Engine<'static>>::validate_action(self, &action)
+            .await
+            .map_err(engine_error)
+    }
+    async fn legal_actions(&self, player: Value) -> Result<Value> {
+        let player: <E as Engine<'static>>::PlayerId = serde_json::from_value(player)?;
+        let actions = <E as Engine<'static>>::legal_actions(self, &player)
+            .await
+            .map_err(engine_error)?;
+        Ok(serde_json::to_value(actions)?)
+    }
+    async fn apply_action(&mut self, action: Value) -> Result<()> {
+        let action: <E as Engine<'static>>::Action = serde_json::from_value(action)?;
+        <E as Engine<'static>>::apply_action(self, action)
+            .await
+            .map_err(engine_error)
+    }
+
+    async fn current_players(&self) -> Result<Value> {
+        let players = <E as Engine<'static>>::current_players(self)
+            .await
+            .map_err(engine_error)?;
+        Ok(serde_json::to_value(players)?)
+    }
+
+    async fn results(&self) -> Result<Value> {
+        let results = <E as Engine<'static>>::results(self)
+            .await
+            .map_err(engine_error)?;
+        Ok(serde_json::to_value(results)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_engine::Counter;
+
+    #[tokio::test]
+    async fn json_round_trip() {
+        let mut engine: Box<dyn DynEngine> = Box::new(Counter { total: 0 });
+
+        engine.apply_action(json!(4)).await.unwrap();
+        assert_eq!(engine.public_state().await.unwrap(), json!(4));
+        assert_eq!(engine.results().await.unwrap(), json!(false));
+        assert_eq!(
+            engine.legal_actions(json!(null)).await.unwrap(),
+            json!([1, 2, 3, 4, 5, 6])
+        );
+    }
+
+    #[tokio::test]
+    async fn malformed_action() {
+        let mut engine: Box<dyn DynEngine> = Box::new(Counter { total: 0 });
+
+        assert!(matches!(
+            engine.apply_action(json!("four")).await,
+            Err(Error::Json(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejected_action() {
+        let mut engine: Box<dyn DynEngine> = Box::new(Counter { total: 9 });
+
+        assert!(matches!(
+            engine.apply_action(json!(2)).await,
+            Err(Error::Engine(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{ActivePlayers, FromPublicState};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GameLogError<E> {
+    #[error("Player is not one of the current players")]
+    NotCurrentPlayer,
+    #[error("Engine error: {0}")]
+    Engine(E),
+}
+
+/// An accepted action together with who played it and when
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct LogEntry<PlayerId, Action> {
+    pub sequence: usize,
+    pub player: PlayerId,
+    pub timestamp: SystemTime,
+    pub action: Action,
+}
+
+/// Append-only record of a game, starting from its initial public state.
+///
+/// Any position of the game can be rebuilt by replaying the recorded actions
+/// on top of the initial state.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct GameLog<State, PlayerId, Action> {
+    initial_state: State,
+    entries: Vec<LogEntry<PlayerId, Action>>,
+}
+
+impl<State, PlayerId, Action> GameLog<State, PlayerId, Action> {
+    pub fn new(initial_state: State) -> Self {
+        Self {
+            initial_state,
+            entries: vec![],
+        }
+    }
+
+    pub fn initial_state(&self) -> &State {
+        &self.initial_state
+    }
+
+    pub fn entries(&self) -> &[LogEntry<PlayerId, Action>] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append an action without going through an engine
+    pub fn record(&mut self, player: PlayerId, action: Action) -> &LogEntry<PlayerId, Action> {
+        self.entries.push(LogEntry {
+            sequence: self.entries.len(),
+            player,
+            timestamp: SystemTime::now(),
+            action,
+        });
+
+        self.entries.last().expect("entry just pushed")
+    }
+
+    /// Apply `action` to `engine` and record it only if `player` is one of the
+    /// current players and the engine accepted it
+    pub async fn apply<'a, E>(
+        &mut self,
+        engine: &mut E,
+        player: PlayerId,
+        action: Action,
+    ) -> Result<&LogEntry<PlayerId, Action>, GameLogError<E::Error>>
+    where
+        E: FromPublicState<'a, PublicState = State, PlayerId = PlayerId, Action = Action>,
+        E::CurrentPlayers: ActivePlayers<PlayerId>,
+        PlayerId: PartialEq,
+        Action: Clone,
+    {
+        let players = engine
+            .current_players()
+            .await
+            .map_err(GameLogError::Engine)?
+            .active_players();
+        if !players.contains(&player) {
+            return Err(GameLogError::NotCurrentPlayer);
+        }
+
+        engine
+            .apply_action(action.clone())
+            .await
+            .map_err(GameLogError::Engine)?;
+        Ok(self.record(player, action))
+    }
+
+    /// Rebuild the engine with every recorded action applied
+    pub async fn replay<'a, E>(&self) -> Result<E, E::Error>
+    where
+        E: FromPublicState<'a, PublicState = State, PlayerId = PlayerId, Action = Action>,
+        State: Clone,
+        Action: Clone,
+    {
+        self.replay_until(self.entries.len()).await
+    }
+
+    /// Rebuild the engine with only the first `sequence` recorded actions applied
+    pub async fn replay_until<'a, E>(&self, sequence: usize) -> Result<E, E::Error>
+    where
+        E: FromPublicState<'a, PublicState = State, PlayerId = PlayerId, Action = Action>,
+        State: Clone,
+        Action: Clone,
+    {
+        let mut engine = E::from_public_state(self.initial_state.clone());
+        for entry in self.entries.iter().take(sequence) {
+            engine.apply_action(entry.action.clone()).await?;
+        }
+
+        Ok(engine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_engine::Counter;
+
+    #[test]
+    fn record_sequence() {
+        let mut log = GameLog::new(0);
+        log.record((), 1);
+        log.record((), 2);
+
+        let sequences: Vec<_> = log.entries().iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn rejected_action_not_recorded() {
+        let mut log = GameLog::new(0);
+        let mut engine = Counter::from_public_state(0);
+
+        log.apply(&mut engine, (), 4).await.unwrap();
+        assert!(log.apply(&mut engine, (), 7).await.is_err());
+
+        assert_eq!(log.len(), 1);
+        assert_eq!(engine.total, 4);
+    }
+
+    #[tokio::test]
+    async fn waiting_player_not_recorded() {
+        let mut log = GameLog::new(0);
+        let mut engine = Counter::from_public_state(10);
+
+        assert_eq!(
+            log.apply(&mut engine, (), 1).await.unwrap_err(),
+            GameLogError::NotCurrentPlayer
+        );
+        assert!(log.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        let mut log = GameLog::new(1);
+        let mut engine = Counter::from_public_state(1);
+        for action in [2, 3, 4] {
+            log.apply(&mut engine, (), action).await.unwrap();
+        }
+
+        let replayed: Counter = log.replay().await.unwrap();
+        assert_eq!(replayed.total, engine.total);
+
+        let replayed: Counter = log.replay_until(2).await.unwrap();
+        assert_eq!(replayed.total, 6);
+
+        let replayed: Counter = log.replay_until(0).await.unwrap();
+        assert_eq!(replayed.total, 1);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::FromPublicState;
+
+/// Identifier of a position inside a [`History`]
+pub type NodeId = usize;
+
+struct Node<State, Action> {
+    state: State,
+    action: Option<Action>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    last_visited: Option<NodeId>,
+}
+
+/// Tree of every position reached in a game.
+///
+/// Each node keeps a snapshot of the public state, so moving around the tree
+/// rebuilds the engine from the snapshot instead of replaying actions. Engines
+/// with private state, like hidden hands or a random generator, lose it on
+/// [`History::undo`], [`History::redo`] and [`History::goto`].
+/// Playing an action from a position that already has continuations starts a
+/// new variation instead of discarding the existing ones.
+pub struct History<'a, E: FromPublicState<'a>> {
+    nodes: Vec<Node<E::PublicState, E::Action>>,
+    current: NodeId,
+    engine: E,
+}
+
+impl<'a, E> History<'a, E>
+where
+    E: FromPublicState<'a>,
+    E::PublicState: Clone,
+    E::Action: Clone + PartialEq,
+{
+    pub const ROOT: NodeId = 0;
+
+    pub fn new(initial_state: E::PublicState) -> Self {
+        let engine = E::from_public_state(initial_state.clone());
+
+        Self {
+            nodes: vec![Node {
+                state: initial_state,
+                action: None,
+                parent: None,
+                children: vec![],
+                last_visited: None,
+            }],
+            current: Self::ROOT,
+            engine,
+        }
+    }
+
+    pub fn engine(&self) -> &E {
+        &self.engine
+    }
+
+    pub fn current(&self) -> NodeId {
+        self.current
+    }
+
+    pub fn state(&self, node: NodeId) -> Option<&E::PublicState> {
+        Some(&self.nodes.get(node)?.state)
+    }
+
+    /// Action that led to `node`, `None` for the root
+    pub fn action(&self, node: NodeId) -> Option<&E::Action> {
+        self.nodes.get(node)?.action.as_ref()
+    }
+
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes.get(node)?.parent
+    }
+
+    /// Continuations already explored from `node`, in the order they were played
+    pub fn variations(&self, node: NodeId) -> &[NodeId] {
+        self.nodes
+            .get(node)
+            .map(|node| node.children.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Actions played from the root up to the current position
+    pub fn line(&self) -> Vec<&E::Action> {
+        let mut line = vec![];
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            line.extend(self.nodes[node].action.as_ref());
+            node = parent;
+        }
+
+        line.reverse();
+        line
+    }
+
+    /// Play `action` from the current position.
+    ///
+    /// If the same action was already explored from here the existing node is
+    /// reused, otherwise a new variation is created.
+    pub async fn apply_action(&mut self, action: E::Action) -> Result<NodeId, E::Error> {
+        let existing = self.nodes[self.current]
+            .children
+            .iter()
+            .find(|child| self.nodes[**child].action.as_ref() == Some(&action))
+            .copied();
+        if let Some(child) = existing {
+            self.goto(child);
+            return Ok(child);
+        }
+
+        self.engine.apply_action(action.clone()).await?;
+        let state = self.engine.public_state().await?.clone();
+
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            state,
+            action: Some(action),
+            parent: Some(self.current),
+            children: vec![],
+            last_visited: None,
+        });
+
+        let parent = &mut self.nodes[self.current];
+        parent.children.push(id);
+        parent.last_visited = Some(id);
+        self.current = id;
+
+        Ok(id)
+    }
+
+    /// Step back to the previous position, returns `false` at the root
+    pub fn undo(&mut self) -> bool {
+        match self.nodes[self.current].parent {
+            Some(parent) => self.goto(parent),
+            None => false,
+        }
+    }
+
+    /// Step forward along the most recently visited variation
+    pub fn redo(&mut self) -> bool {
+        match self.nodes[self.current].last_visited {
+            Some(child) => self.goto(child),
+            None => false,
+        }
+    }
+
+    /// Jump to any position of the tree, returns `false` if `node` doesn't exist
+    pub fn goto(&mut self, node: NodeId) -> bool {
+        if node >= self.nodes.len() {
+            return false;
+        }
+
+        let mut child = node;
+        while let Some(parent) = self.nodes[child].parent {
+            self.nodes[parent].last_visited = Some(child);
+            child = parent;
+        }
+
+        self.engine = E::from_public_state(self.nodes[node].state.clone());
+        self.current = node;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_engine::Counter;
+
+    #[tokio::test]
+    async fn undo_redo() {
+        let mut history = History::<Counter>::new(0);
+        history.apply_action(2).await.unwrap();
+        history.apply_action(3).await.unwrap();
+        assert_eq!(history.engine().total, 5);
+
+        assert!(history.undo());
+        assert_eq!(history.engine().total, 2);
+        assert!(history.undo());
+        assert_eq!(history.engine().total, 0);
+        assert!(!history.undo());
+
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.engine().total, 5);
+        assert!(!history.redo());
+    }
+
+    #[tokio::test]
+    async fn branch_variation() {
+        let mut history = History::<Counter>::new(0);
+        let main = history.apply_action(2).await.unwrap();
+        history.apply_action(3).await.unwrap();
+
+        history.goto(main);
+        let variation = history.apply_action(1).await.unwrap();
+        assert_eq!(history.engine().total, 3);
+        assert_eq!(history.variations(main).len(), 2);
+        assert_eq!(history.line(), vec![&2, &1]);
+
+        // redo follows the variation visited last
+        history.goto(main);
+        assert!(history.redo());
+        assert_eq!(history.current(), variation);
+    }
+
+    #[tokio::test]
+    async fn reuse_explored_action() {
+        let mut history = History::<Counter>::new(0);
+        let first = history.apply_action(2).await.unwrap();
+        history.undo();
+
+        assert_eq!(history.apply_action(2).await.unwrap(), first);
+        assert_eq!(history.variations(History::<Counter>::ROOT).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_action_keeps_position() {
+        let mut history = History::<Counter>::new(8);
+        assert!(history.apply_action(5).await.is_err());
+
+        assert_eq!(history.current(), History::<Counter>::ROOT);
+        assert_eq!(history.engine().total, 8);
+    }
+}
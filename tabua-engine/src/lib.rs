@@ -3,6 +3,12 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+pub mod dyn_engine;
+pub mod game_log;
+pub mod history;
+#[cfg(test)]
+mod test_engine;
+
 #[async_trait]
 pub trait Engine<'a> {
     type PublicState: Serialize + Deserialize<'a>;
@@ -20,9 +26,41 @@ pub trait Engine<'a> {
     ) -> Result<Vec<Self::PrivateState>, Self::Error>;
 
     async fn validate_action(&self, action: &Self::Action) -> Result<(), Self::Error>;
+    /// Every action `player` is allowed to take in the current state
+    async fn legal_actions(
+        &self,
+        player: &Self::PlayerId,
+    ) -> Result<Vec<Self::Action>, Self::Error>;
     async fn apply_action(&mut self, action: Self::Action) -> Result<(), Self::Error>;
 
     async fn current_players(&self) -> Result<Self::CurrentPlayers, Self::Error>;
 
     async fn results(&self) -> Result<Self::EndGame, Self::Error>;
 }
+
+/// Engines that can be rebuilt from a snapshot of their public state.
+///
+/// The rebuilt engine only knows the public state, private state such as
+/// hidden hands or a random generator is reset.
+pub trait FromPublicState<'a>: Engine<'a> {
+    fn from_public_state(state: Self::PublicState) -> Self;
+}
+
+/// Players able to act, as returned by [`Engine::current_players`]
+pub trait ActivePlayers<PlayerId> {
+    fn active_players(self) -> Vec<PlayerId>;
+}
+
+impl<PlayerId> ActivePlayers<PlayerId> for Vec<PlayerId> {
+    fn active_players(self) -> Vec<PlayerId> {
+        self
+    }
+}
+
+/// Final standing of a game, as returned by [`Engine::results`]
+pub trait Outcome<PlayerId> {
+    fn is_over(&self) -> bool;
+
+    /// Reward of `player` once the game is over, from 0.0 (loss) to 1.0 (win)
+    fn reward(&self, player: &PlayerId) -> f64;
+}
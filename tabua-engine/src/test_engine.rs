@@ -0,0 +1,61 @@
+use async_trait::async_trait;
+
+use crate::{Engine, FromPublicState};
+
+/// Single player game where every action adds to a counter, up to 10 where it ends
+pub struct Counter {
+    pub total: u32,
+}
+
+#[async_trait]
+impl Engine<'_> for Counter {
+    type PublicState = u32;
+    type PrivateState = ();
+    type PlayerId = ();
+    type Action = u32;
+    type EndGame = bool;
+    type Error = &'static str;
+
+    async fn public_state(&self) -> Result<&Self::PublicState, Self::Error> {
+        Ok(&self.total)
+    }
+    async fn private_state(
+        &self,
+        _user: &Self::PlayerId,
+    ) -> Result<Vec<Self::PrivateState>, Self::Error> {
+        Ok(vec![])
+    }
+
+    async fn validate_action(&self, action: &Self::Action) -> Result<(), Self::Error> {
+        if self.total + action > 10 {
+            return Err("counter overflow");
+        }
+
+        Ok(())
+    }
+    async fn legal_actions(
+        &self,
+        _player: &Self::PlayerId,
+    ) -> Result<Vec<Self::Action>, Self::Error> {
+        Ok((1..=10_u32.saturating_sub(self.total)).collect())
+    }
+    async fn apply_action(&mut self, action: Self::Action) -> Result<(), Self::Error> {
+        self.validate_action(&action).await?;
+        self.total += action;
+        Ok(())
+    }
+
+    async fn current_players(&self) -> Result<Self::CurrentPlayers, Self::Error> {
+        Ok(if self.total < 10 { vec![()] } else { vec![] })
+    }
+
+    async fn results(&self) -> Result<Self::EndGame, Self::Error> {
+        Ok(self.total == 10)
+    }
+}
+
+impl FromPublicState<'_> for Counter {
+    fn from_public_state(total: u32) -> Self {
+        Self { total }
+    }
+}
@@ -0,0 +1,572 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not, Shl, Shr};
+
+use super::grid::{
+    CellAdjacency, CellType, ColumnCellIterator, ColumnIterator, Grid, GridBuilder, GridExt,
+    GridExtMut, Position2d, Position2dDiff,
+};
+
+/// Set of up to `W * 64` bits, bit 0 being the lowest bit of the first word
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Bitboard<const W: usize>(pub [u64; W]);
+
+impl<const W: usize> Bitboard<W> {
+    pub const EMPTY: Self = Self([0; W]);
+
+    pub fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn clear(&mut self, index: usize) {
+        self.0[index / 64] &= !(1 << (index % 64));
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    /// Indexes of the set bits, in increasing order
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl<const W: usize> Default for Bitboard<W> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl<const W: usize> BitAnd for Bitboard<W> {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        self &= rhs;
+        self
+    }
+}
+
+impl<const W: usize> BitAndAssign for Bitboard<W> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a &= b);
+    }
+}
+
+impl<const W: usize> BitOr for Bitboard<W> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const W: usize> BitOrAssign for Bitboard<W> {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a |= b);
+    }
+}
+
+impl<const W: usize> BitXor for Bitboard<W> {
+    type Output = Self;
+
+    fn bitxor(mut self, rhs: Self) -> Self::Output {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a ^= b);
+        self
+    }
+}
+
+impl<const W: usize> Not for Bitboard<W> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(self.0.map(|word| !word))
+    }
+}
+
+/// Moves every bit towards higher indexes, dropping the ones going past the end
+impl<const W: usize> Shl<usize> for Bitboard<W> {
+    type Output = Self;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        let (words, bits) = (rhs / 64, rhs % 64);
+        let mut result = Self::EMPTY;
+        for i in words..W {
+            result.0[i] = self.0[i - words] << bits;
+            if bits > 0 && i > words {
+                result.0[i] |= self.0[i - words - 1] >> (64 - bits);
+            }
+        }
+        result
+    }
+}
+
+/// Moves every bit towards lower indexes, dropping the ones going below 0
+impl<const W: usize> Shr<usize> for Bitboard<W> {
+    type Output = Self;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        let (words, bits) = (rhs / 64, rhs % 64);
+        let mut result = Self::EMPTY;
+        for i in 0..W.saturating_sub(words) {
+            result.0[i] = self.0[i + words] >> bits;
+            if bits > 0 && i + words + 1 < W {
+                result.0[i] |= self.0[i + words + 1] << (64 - bits);
+            }
+        }
+        result
+    }
+}
+
+/// Cell values handed out by [`BitGrid`] as [`GridExt`], `PIECES[0]` is the empty cell
+static PIECES: [Option<usize>; 65] = {
+    let mut pieces = [None; 65];
+    let mut piece = 0;
+    while piece < 64 {
+        pieces[piece + 1] = Some(piece);
+        piece += 1;
+    }
+    pieces
+};
+
+/// Square board holding `P` kinds of piece as one [`Bitboard`] each.
+///
+/// Cells are stored row by row with an always empty guard bit closing each
+/// row, so a board needs `rows * (columns + 1)` bits out of the `W * 64`
+/// available. The guard bits stop shifts from wrapping around rows, which
+/// makes neighbor and line queries a handful of word operations.
+///
+/// As a [`GridExt`] the cells are the piece indexes, `None` when empty. Cells are
+/// bits that can't be borrowed mutably, change them with [`BitGrid::set`] and
+/// [`BitGrid::clear`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct BitGrid<const P: usize, const W: usize = 1> {
+    rows: usize,
+    columns: usize,
+    /// Every cell of the board, the guard bits left out
+    cells: Bitboard<W>,
+    cell_adjacency: CellAdjacency,
+    pieces: [Bitboard<W>; P],
+}
+
+impl<const P: usize, const W: usize> BitGrid<P, W> {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        assert!(P <= 64, "at most 64 kinds of piece");
+        assert!(
+            rows * (columns + 1) <= W * 64,
+            "{rows}x{columns} board does not fit in {W} words"
+        );
+
+        let mut row = Bitboard::EMPTY;
+        for col in 0..columns {
+            row.set(col);
+        }
+        let mut cells = Bitboard::EMPTY;
+        for _ in 0..rows {
+            cells = (cells << (columns + 1)) | row;
+        }
+
+        Self {
+            rows,
+            columns,
+            cells,
+            cell_adjacency: CellAdjacency::Side,
+            pieces: [Bitboard::EMPTY; P],
+        }
+    }
+
+    pub fn with_cell_adjacency(mut self, cell_adjacency: CellAdjacency) -> Self {
+        self.cell_adjacency = cell_adjacency;
+        self
+    }
+
+    /// Bitboard copy of a square grid, `piece` maps a cell to its piece index or `None` when empty
+    pub fn from_grid<T, F>(grid: &Grid<T>, piece: F) -> Self
+    where
+        F: Fn(&T) -> Option<usize>,
+    {
+        assert_eq!(grid.cell_type(), CellType::Square, "only square grids");
+
+        let mut board =
+            Self::new(grid.row_len(), grid.column_len()).with_cell_adjacency(grid.cell_adjacency());
+        for (row, cells) in grid.rows().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                board.set(&(row, col), piece(cell));
+            }
+        }
+        board
+    }
+
+    pub fn to_grid(&self) -> Grid<Option<usize>> {
+        let mut grid = GridBuilder::new_square_grid()
+            .with_cell_adjacency(self.cell_adjacency)
+            .with_rows(self.rows)
+            .with_columns(self.columns)
+            .with_initial_value(None)
+            .build();
+        for piece in 0..P {
+            for position in self.positions(&self.pieces[piece]) {
+                *grid.get_mut(&position).expect("inside the board") = Some(piece);
+            }
+        }
+        grid
+    }
+
+    fn stride(&self) -> usize {
+        self.columns + 1
+    }
+
+    pub fn index(&self, position: &Position2d) -> Option<usize> {
+        (position.0 < self.rows && position.1 < self.columns)
+            .then(|| position.0 * self.stride() + position.1)
+    }
+
+    pub fn position(&self, index: usize) -> Option<Position2d> {
+        let position = (index / self.stride(), index % self.stride());
+        (position.0 < self.rows && position.1 < self.columns).then_some(position)
+    }
+
+    /// Positions of the cells set in `bitboard`
+    pub fn positions<'b>(
+        &'b self,
+        bitboard: &'b Bitboard<W>,
+    ) -> impl Iterator<Item = Position2d> + 'b {
+        bitboard.ones().filter_map(|index| self.position(index))
+    }
+
+    pub fn piece_at(&self, position: &Position2d) -> Option<usize> {
+        let index = self.index(position)?;
+        (0..P).find(|piece| self.pieces[*piece].get(index))
+    }
+
+    /// Put `piece` on `position`, replacing whatever stood there
+    pub fn set(&mut self, position: &Position2d, piece: Option<usize>) {
+        let index = self.index(position).expect("position out of the board");
+        for bitboard in &mut self.pieces {
+            bitboard.clear(index);
+        }
+        if let Some(piece) = piece {
+            self.pieces[piece].set(index);
+        }
+    }
+
+    /// Remove whatever stood on `position`
+    pub fn clear(&mut self, position: &Position2d) {
+        self.set(position, None);
+    }
+
+    pub fn pieces(&self, piece: usize) -> Bitboard<W> {
+        self.pieces[piece]
+    }
+
+    pub fn count(&self, piece: usize) -> u32 {
+        self.pieces[piece].count_ones()
+    }
+
+    /// Every cell of the board
+    pub fn cells(&self) -> Bitboard<W> {
+        self.cells
+    }
+
+    pub fn occupied(&self) -> Bitboard<W> {
+        self.pieces
+            .iter()
+            .fold(Bitboard::EMPTY, |occupied, piece| occupied | *piece)
+    }
+
+    pub fn empty_cells(&self) -> Bitboard<W> {
+        self.cells & !self.occupied()
+    }
+
+    /// Move every cell of `bitboard` one step in `direction`, dropping those leaving the board
+    pub fn shift(&self, bitboard: Bitboard<W>, direction: Position2dDiff) -> Bitboard<W> {
+        let offset = direction.0 as isize * self.stride() as isize + direction.1 as isize;
+        let shifted = if offset >= 0 {
+            bitboard << offset as usize
+        } else {
+            bitboard >> offset.unsigned_abs()
+        };
+
+        shifted & self.cells
+    }
+
+    /// Cells adjacent to some cell of `bitboard` but not part of it
+    pub fn neighbors(&self, bitboard: Bitboard<W>) -> Bitboard<W> {
+        let neighbors = self
+            .cell_adjacency
+            .square_offsets()
+            .into_iter()
+            .fold(Bitboard::EMPTY, |neighbors, direction| {
+                neighbors | self.shift(bitboard, direction)
+            });
+
+        neighbors & !bitboard
+    }
+
+    fn line_directions(&self) -> Vec<Position2dDiff> {
+        match self.cell_adjacency {
+            CellAdjacency::Side => vec![(0, 1), (1, 0)],
+            CellAdjacency::SideAndVertex => vec![(0, 1), (1, 0), (1, 1), (1, -1)],
+        }
+    }
+
+    /// First cells of the runs of at least `length` `piece`s along `direction`
+    pub fn line_starts(
+        &self,
+        piece: usize,
+        direction: Position2dDiff,
+        length: usize,
+    ) -> Bitboard<W> {
+        let pieces = self.pieces[piece];
+        let back = (-direction.0, -direction.1);
+
+        let mut starts = pieces;
+        for _ in 1..length {
+            starts = pieces & self.shift(starts, back);
+        }
+        starts
+    }
+
+    /// Whether `piece` has `length` cells in a row along any line of the grid adjacency
+    pub fn has_line(&self, piece: usize, length: usize) -> bool {
+        self.line_directions()
+            .into_iter()
+            .any(|direction| !self.line_starts(piece, direction, length).is_empty())
+    }
+}
+
+impl<'a, const P: usize, const W: usize> GridExt<'a, Option<usize>> for BitGrid<P, W> {
+    type RowCells = BitRowCells<'a, P, W>;
+    type Rows = BitRows<'a, P, W>;
+
+    /// Empty when `row` is outside the board
+    fn iter_row(&'a self, row: usize) -> Self::RowCells {
+        BitRowCells {
+            grid: self,
+            position: (row, 0),
+        }
+    }
+    fn iter_column(&'a self, col: usize) -> ColumnCellIterator<'a, Option<usize>> {
+        ColumnCellIterator::new(self, col)
+    }
+
+    fn row_len(&self) -> usize {
+        self.rows
+    }
+    fn column_len(&self) -> usize {
+        self.columns
+    }
+
+    fn rows(&'a self) -> Self::Rows {
+        BitRows { grid: self, row: 0 }
+    }
+    fn columns(&'a self) -> ColumnIterator<'a, Option<usize>> {
+        ColumnIterator::new(self)
+    }
+
+    fn get(&'a self, position: &Position2d) -> Option<&'a Option<usize>> {
+        self.index(position)?;
+        Some(&PIECES[self.piece_at(position).map_or(0, |piece| piece + 1)])
+    }
+
+    fn adjacencies(&self, _position: &Position2d) -> Vec<Position2dDiff> {
+        self.cell_adjacency.square_offsets()
+    }
+    fn adjacent_cells(&'a self, current: &Position2d) -> Vec<(Position2d, &'a Option<usize>)> {
+        self.adjacencies(current)
+            .into_iter()
+            .filter_map(|diff| {
+                Some((
+                    current.0.checked_add_signed(diff.0 as isize)?,
+                    current.1.checked_add_signed(diff.1 as isize)?,
+                ))
+            })
+            .filter_map(|p| Some((p, self.get(&p)?)))
+            .collect()
+    }
+}
+
+/// Rows of a [`BitGrid`], see [`GridExt::rows`]
+pub struct BitRows<'a, const P: usize, const W: usize> {
+    grid: &'a BitGrid<P, W>,
+    row: usize,
+}
+
+impl<'a, const P: usize, const W: usize> Iterator for BitRows<'a, P, W> {
+    type Item = BitRowCells<'a, P, W>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row >= self.grid.rows {
+            return None;
+        }
+
+        let result = self.grid.iter_row(self.row);
+        self.row += 1;
+        Some(result)
+    }
+}
+
+/// Cells of a row of a [`BitGrid`], see [`GridExt::iter_row`]
+pub struct BitRowCells<'a, const P: usize, const W: usize> {
+    grid: &'a BitGrid<P, W>,
+    position: Position2d,
+}
+
+impl<'a, const P: usize, const W: usize> Iterator for BitRowCells<'a, P, W> {
+    type Item = &'a Option<usize>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.grid.get(&self.position)?;
+        self.position.1 += 1;
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: usize = 0;
+    const O: usize = 1;
+
+    fn board<const W: usize>(rows: &[&str]) -> BitGrid<2, W> {
+        let grid = Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect());
+        BitGrid::from_grid(&grid, |cell: &char| match cell {
+            'x' => Some(X),
+            'o' => Some(O),
+            _ => None,
+        })
+        .with_cell_adjacency(CellAdjacency::SideAndVertex)
+    }
+
+    #[test]
+    fn shifts_across_words() {
+        let mut bitboard = Bitboard::<2>::EMPTY;
+        bitboard.set(62);
+
+        let shifted = bitboard << 3;
+        assert_eq!(shifted.ones().collect::<Vec<_>>(), vec![65]);
+        assert_eq!((shifted >> 65).ones().collect::<Vec<_>>(), vec![0]);
+        assert!((shifted << 63).is_empty());
+    }
+
+    #[test]
+    fn set_and_get() {
+        let mut grid = BitGrid::<2>::new(3, 3);
+        grid.set(&(1, 2), Some(O));
+        grid.set(&(0, 0), Some(X));
+        grid.set(&(0, 0), Some(O));
+
+        assert_eq!(grid.piece_at(&(1, 2)), Some(O));
+        assert_eq!(grid.piece_at(&(0, 0)), Some(O));
+        assert_eq!(grid.piece_at(&(2, 2)), None);
+        assert_eq!(grid.count(O), 2);
+        assert_eq!(grid.count(X), 0);
+        assert_eq!(grid.empty_cells().count_ones(), 7);
+
+        grid.clear(&(1, 2));
+        assert_eq!(grid.piece_at(&(1, 2)), None);
+        assert_eq!(grid.count(O), 1);
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let grid = board::<1>(&["x.o", ".x.", "o.."]);
+        let expected = Grid::new_square_grid(vec![
+            vec![Some(X), None, Some(O)],
+            vec![None, Some(X), None],
+            vec![Some(O), None, None],
+        ]);
+
+        assert_eq!(
+            grid.to_grid().rows().collect::<Vec<_>>(),
+            expected.rows().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn grid_ext() {
+        let grid = board::<1>(&["x.o", ".x."]);
+
+        assert_eq!(grid.row_len(), 2);
+        assert_eq!(grid.column_len(), 3);
+        assert_eq!(grid.get(&(0, 2)), Some(&Some(O)));
+        assert_eq!(grid.get(&(0, 3)), None);
+        assert_eq!(
+            grid.iter_row(1).copied().collect::<Vec<_>>(),
+            vec![None, Some(X), None]
+        );
+        assert_eq!(grid.adjacent_cells(&(0, 0)).len(), 3);
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec![Some(X), None, Some(O)], vec![None, Some(X), None]]
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let grid = board::<1>(&["x.o", "xoo", "oxx"]);
+
+        assert!(grid.has_line(O, 3));
+        assert!(!grid.has_line(X, 3));
+        assert_eq!(
+            grid.positions(&grid.line_starts(O, (1, -1), 3))
+                .collect::<Vec<_>>(),
+            vec![(0, 2)]
+        );
+        assert!(grid.has_line(X, 2));
+    }
+
+    #[test]
+    fn lines_do_not_wrap() {
+        // (0, 2) and (1, 0) follow each other in memory but not on the board
+        let grid = board::<1>(&["..x", "x..", "..."]);
+        assert!(!grid.has_line(X, 2));
+
+        let grid = board::<1>(&["x..", "..x", "..."]);
+        assert!(!grid.has_line(X, 2));
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = board::<1>(&["...", ".x.", "..."]).with_cell_adjacency(CellAdjacency::Side);
+        let neighbors = grid.neighbors(grid.pieces(X));
+
+        assert_eq!(
+            grid.positions(&neighbors).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (1, 2), (2, 1)]
+        );
+
+        let corner = board::<1>(&["x..", "...", "..."]);
+        assert_eq!(corner.neighbors(corner.pieces(X)).count_ones(), 3);
+    }
+
+    #[test]
+    fn large_board() {
+        let mut grid = BitGrid::<2, 2>::new(10, 10);
+        for col in 3..8 {
+            grid.set(&(9, col), Some(X));
+        }
+
+        assert!(grid.has_line(X, 5));
+        assert!(!grid.has_line(X, 6));
+        assert_eq!(grid.cells().count_ones(), 100);
+    }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::neighbors::Neighbors;
+
+pub type NodeId = usize;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GraphError {
+    #[error("Edge {edge} references node {node} but there are {nodes} nodes")]
+    UnknownNode {
+        edge: usize,
+        node: NodeId,
+        nodes: usize,
+    },
+}
+
+fn unit_weight() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Edge<E> {
+    pub from: NodeId,
+    pub to: NodeId,
+    /// Cost of walking the edge, 1 when left out of a saved map
+    #[serde(default = "unit_weight")]
+    pub weight: u32,
+    pub label: E,
+}
+
+impl<E> Edge<E> {
+    /// End of the edge opposite to `node`
+    pub fn other(&self, node: NodeId) -> NodeId {
+        if self.from == node {
+            self.to
+        } else {
+            self.from
+        }
+    }
+}
+
+/// Board made of nodes linked by edges, like the points and lines of Nine
+/// Men's Morris or the cities and routes of a map.
+///
+/// Edges of undirected graphs are walked both ways, edges of directed ones
+/// only from `from` to `to`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(try_from = "RawGraph<N, E>")]
+pub struct GraphBoard<N, E = ()> {
+    directed: bool,
+    nodes: Vec<N>,
+    edges: Vec<Edge<E>>,
+    /// Indexes in `edges` of the edges walkable from each node
+    #[serde(skip_serializing)]
+    outgoing: Vec<Vec<usize>>,
+}
+
+/// Deserialized graph, before checking its edges
+#[derive(Deserialize)]
+struct RawGraph<N, E> {
+    #[serde(default)]
+    directed: bool,
+    nodes: Vec<N>,
+    edges: Vec<Edge<E>>,
+}
+
+impl<N, E> TryFrom<RawGraph<N, E>> for GraphBoard<N, E> {
+    type Error = GraphError;
+
+    fn try_from(raw: RawGraph<N, E>) -> Result<Self, Self::Error> {
+        let mut graph = Self {
+            directed: raw.directed,
+            outgoing: raw.nodes.iter().map(|_| vec![]).collect(),
+            nodes: raw.nodes,
+            edges: Vec::with_capacity(raw.edges.len()),
+        };
+        for edge in raw.edges {
+            graph.add_edge(edge.from, edge.to, edge.weight, edge.label)?;
+        }
+        Ok(graph)
+    }
+}
+
+impl<N, E> GraphBoard<N, E> {
+    pub fn new() -> Self {
+        Self {
+            directed: false,
+            nodes: vec![],
+            edges: vec![],
+            outgoing: vec![],
+        }
+    }
+
+    pub fn new_directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        self.nodes.push(node);
+        self.outgoing.push(vec![]);
+        self.nodes.len() - 1
+    }
+
+    /// Link two nodes, returning the index of the new edge
+    pub fn add_edge(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        weight: u32,
+        label: E,
+    ) -> Result<usize, GraphError> {
+        let index = self.edges.len();
+        if let Some(node) = [from, to]
+            .into_iter()
+            .find(|node| *node >= self.nodes.len())
+        {
+            return Err(GraphError::UnknownNode {
+                edge: index,
+                node,
+                nodes: self.nodes.len(),
+            });
+        }
+
+        self.outgoing[from].push(index);
+        if !self.directed && from != to {
+            self.outgoing[to].push(index);
+        }
+        self.edges.push(Edge {
+            from,
+            to,
+            weight,
+            label,
+        });
+        Ok(index)
+    }
+
+    pub fn node(&self, node: NodeId) -> Option<&N> {
+        self.nodes.get(node)
+    }
+
+    pub fn node_mut(&mut self, node: NodeId) -> Option<&mut N> {
+        self.nodes.get_mut(node)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    pub fn edges(&self) -> &[Edge<E>] {
+        &self.edges
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Edges walkable from `node`, with the node each one leads to
+    pub fn edges_from(&self, node: NodeId) -> Vec<(NodeId, &Edge<E>)> {
+        self.outgoing
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|index| {
+                let edge = &self.edges[*index];
+                (edge.other(node), edge)
+            })
+            .collect()
+    }
+
+    /// Weight of the lightest edge walkable from `from` to `to`, usable as a
+    /// [`Dijkstra`](crate::pathfinder::dijkstra::Dijkstra) cost
+    pub fn weight(&self, from: &NodeId, to: &NodeId) -> Option<u32> {
+        self.edges_from(*from)
+            .into_iter()
+            .filter(|(next, _)| next == to)
+            .map(|(_, edge)| edge.weight)
+            .min()
+    }
+}
+
+impl<N, E> Default for GraphBoard<N, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N, E> Neighbors for GraphBoard<N, E> {
+    type Position = NodeId;
+    type Cell = N;
+
+    fn cell(&self, node: &NodeId) -> Option<&N> {
+        self.node(*node)
+    }
+
+    fn neighbors(&self, node: &NodeId) -> Vec<(NodeId, &N)> {
+        let mut neighbors: Vec<NodeId> = vec![];
+        for (next, _) in self.edges_from(*node) {
+            if !neighbors.contains(&next) {
+                neighbors.push(next);
+            }
+        }
+
+        neighbors
+            .into_iter()
+            .map(|next| (next, &self.nodes[next]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinder::bfs::BreadthFirstSearch;
+    use crate::pathfinder::dfs::DepthFirstSearch;
+    use crate::pathfinder::dijkstra::Dijkstra;
+
+    const MAP: &str = r#"{
+        "nodes": ["Paris", "Berlin", "Madrid", "Roma"],
+        "edges": [
+            { "from": 0, "to": 1, "weight": 4, "label": "grey" },
+            { "from": 0, "to": 2, "weight": 3, "label": "orange" },
+            { "from": 2, "to": 3, "weight": 2, "label": "blue" },
+            { "from": 3, "to": 1, "label": "grey" }
+        ]
+    }"#;
+
+    #[test]
+    fn load_map() {
+        let map: GraphBoard<String, String> = serde_json::from_str(MAP).unwrap();
+
+        assert!(!map.is_directed());
+        assert_eq!(map.node_count(), 4);
+        assert_eq!(map.edge_count(), 4);
+        assert_eq!(map.node(3).map(String::as_str), Some("Roma"));
+        assert_eq!(map.edges()[3].weight, 1);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(
+            serde_json::from_str::<GraphBoard<String, String>>(&json).unwrap(),
+            map
+        );
+    }
+
+    #[test]
+    fn reject_unknown_node() {
+        let mut graph: GraphBoard<()> = GraphBoard::new();
+        graph.add_node(());
+
+        assert_eq!(
+            graph.add_edge(0, 1, 1, ()),
+            Err(GraphError::UnknownNode {
+                edge: 0,
+                node: 1,
+                nodes: 1
+            })
+        );
+
+        let json = r#"{ "nodes": [null], "edges": [{ "from": 0, "to": 4, "label": null }] }"#;
+        assert!(serde_json::from_str::<GraphBoard<()>>(json).is_err());
+    }
+
+    #[test]
+    fn neighbors() {
+        let map: GraphBoard<String, String> = serde_json::from_str(MAP).unwrap();
+        let names = |node| -> Vec<&str> {
+            map.neighbors(&node)
+                .into_iter()
+                .map(|(_, name)| name.as_str())
+                .collect()
+        };
+
+        assert_eq!(names(0), vec!["Berlin", "Madrid"]);
+        assert_eq!(names(1), vec!["Paris", "Roma"]);
+        assert_eq!(map.weight(&1, &0), Some(4));
+        assert_eq!(map.weight(&1, &2), None);
+    }
+
+    #[test]
+    fn directed() {
+        let mut graph = GraphBoard::new_directed();
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        graph.add_edge(a, b, 1, ()).unwrap();
+
+        assert_eq!(graph.neighbors(&a), vec![(b, &'b')]);
+        assert!(graph.neighbors(&b).is_empty());
+        assert_eq!(graph.bfs(&b, &a), None);
+    }
+
+    #[test]
+    fn pathfinding() {
+        let map: GraphBoard<String, String> = serde_json::from_str(MAP).unwrap();
+
+        assert_eq!(map.bfs(&2, &1), Some(vec![2, 0, 1]));
+        assert!(map.dfs(&2, &1).is_some());
+        assert_eq!(
+            map.dijkstra(&2, &1, |from, to, _| map.weight(from, to)),
+            Some((vec![2, 3, 1], 3))
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use std::ops::{Deref, DerefMut};
+use std::slice::ChunksExact;
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::hex::{HexCoord, HexLayout};
+use super::triangle::TriCoord;
 
 pub type RowIterator<'a, T> = std::slice::Iter<'a, Vec<T>>;
 pub type RowCellIterator<'a, T> = std::slice::Iter<'a, T>;
@@ -8,7 +13,7 @@ pub type RowCellIterator<'a, T> = std::slice::Iter<'a, T>;
 pub type Position2d = (usize, usize);
 pub type Position2dDiff = (i32, i32);
 
-trait AddTuple<T> {
+pub(super) trait AddTuple<T> {
     type Output;
 
     fn add(self, rhs: T) -> Self::Output;
@@ -25,57 +30,287 @@ impl AddTuple<Position2dDiff> for Position2d {
     }
 }
 
-/// Helper trait for useful square grid board functions
-pub trait GridExt<'a, T> {
-    fn iter_row(&self, row: usize) -> RowCellIterator<T>;
-    fn iter_column(&self, col: usize) -> ColumnCellIterator<T>;
+/// Helper trait for useful square grid board functions, see [`GridExtMut`] to change cells
+pub trait GridExt<'a, T: 'a> {
+    type RowCells: Iterator<Item = &'a T>
+    where
+        Self: Sized;
+    type Rows: Iterator<Item: IntoIterator<Item = &'a T>>
+    where
+        Self: Sized;
+
+    fn iter_row(&'a self, row: usize) -> Self::RowCells
+    where
+        Self: Sized;
+    fn iter_column(&'a self, col: usize) -> ColumnCellIterator<'a, T>;
 
     fn row_len(&self) -> usize;
     fn column_len(&self) -> usize;
 
-    fn rows(&self) -> RowIterator<T>;
-    fn columns(&self) -> ColumnIterator<T>;
+    fn rows(&'a self) -> Self::Rows
+    where
+        Self: Sized;
+    fn columns(&'a self) -> ColumnIterator<'a, T>;
+
+    /// Diagonals going down and right, from the bottom left corner to the top right one
+    fn diagonals(&'a self) -> DiagonalIterator<'a, T>
+    where
+        Self: Sized,
+    {
+        DiagonalIterator::new(self)
+    }
+    /// Diagonals going down and left, from the top left corner to the bottom right one
+    fn anti_diagonals(&'a self) -> DiagonalIterator<'a, T>
+    where
+        Self: Sized,
+    {
+        DiagonalIterator::new_anti(self)
+    }
+    /// Cells of the diagonal through `position`, from its top left end
+    fn diagonal_through(&'a self, position: &Position2d) -> DiagonalCellIterator<'a, T>
+    where
+        Self: Sized,
+    {
+        DiagonalCellIterator::through(self, position)
+    }
+    /// Cells of the anti-diagonal through `position`, from its top right end
+    fn anti_diagonal_through(&'a self, position: &Position2d) -> DiagonalCellIterator<'a, T>
+    where
+        Self: Sized,
+    {
+        DiagonalCellIterator::anti_through(self, position)
+    }
 
     fn get(&'a self, position: &Position2d) -> Option<&'a T>;
-    fn get_mut(&'a mut self, position: &Position2d) -> Option<&'a mut T>;
 
-    fn adjacencies(&self) -> Vec<Position2dDiff>;
+    /// Offsets of the cells adjacent to `position`, hex and triangle layouts depend on its parity
+    fn adjacencies(&self, position: &Position2d) -> Vec<Position2dDiff>;
     fn adjacent_cells(&'a self, current: &Position2d) -> Vec<(Position2d, &'a T)>;
 }
 
+/// Grids whose cells can be changed in place
+pub trait GridExtMut<'a, T: 'a>: GridExt<'a, T> {
+    fn get_mut(&'a mut self, position: &Position2d) -> Option<&'a mut T>;
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(from = "RawCellType")]
 pub enum CellType {
     Square,
+    Hex(HexLayout),
+    /// Alternating up and down triangles, see [`TriCoord`]
+    Triangle,
+}
+
+/// Deserialized cell type, also accepting the format from before hex layouts
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCellType {
+    Current(CurrentCellType),
+    Legacy(LegacyCellType),
+}
+
+#[derive(Deserialize)]
+enum CurrentCellType {
+    Square,
+    Hex(HexLayout),
+    Triangle,
+}
+
+/// Hex cells were the bare `"Hex"` string, always laid out as [`HexLayout::OddR`]
+#[derive(Deserialize)]
+enum LegacyCellType {
     Hex,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+impl From<RawCellType> for CellType {
+    fn from(raw: RawCellType) -> Self {
+        match raw {
+            RawCellType::Current(CurrentCellType::Square) => CellType::Square,
+            RawCellType::Current(CurrentCellType::Hex(layout)) => CellType::Hex(layout),
+            RawCellType::Current(CurrentCellType::Triangle) => CellType::Triangle,
+            RawCellType::Legacy(LegacyCellType::Hex) => CellType::Hex(HexLayout::default()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum CellAdjacency {
     Side,
+    /// Same as `Side` for hex cells, as hexagons sharing a vertex also share a side
     SideAndVertex,
 }
 
+impl CellAdjacency {
+    /// Offsets of the cells adjacent to any square cell
+    pub fn square_offsets(&self) -> Vec<Position2dDiff> {
+        match self {
+            #[rustfmt::skip]
+            CellAdjacency::Side => vec![
+                        (-1, 0),
+                (0, -1),         (0, 1),
+                        ( 1, 0)],
+            #[rustfmt::skip]
+            CellAdjacency::SideAndVertex => vec![
+                (-1, -1), (-1, 0), (-1, 1),
+                ( 0, -1),          ( 0, 1),
+                ( 1, -1), ( 1, 0), ( 1, 1),
+            ],
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GridError {
+    #[error("Row {row} has {len} cells, expected {expected}")]
+    JaggedRow {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    #[error("{len} cells cannot fill a {rows}x{columns} grid")]
+    CellCount {
+        rows: usize,
+        columns: usize,
+        len: usize,
+    },
+}
+
+/// How the edges of a grid connect, a wrapped axis makes cells on opposite edges adjacent.
+///
+/// Wrapped hex grids need an even number of rows for `OddR`/`EvenR` layouts,
+/// or of columns for `OddQ`/`EvenQ`, to keep the layout consistent across the seam.
+/// Wrapped triangle grids need an even length along the wrapped axes for the same reason.
+/// Axes of odd length on those grids are not wrapped, stepping past them gives `None`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Topology {
+    #[default]
+    Bounded,
+    /// Left and right edges are joined, like a cylinder standing up
+    WrapColumns,
+    /// Top and bottom edges are joined, like a cylinder lying down
+    WrapRows,
+    /// Both pairs of edges are joined
+    Torus,
+}
+
+impl Topology {
+    pub fn wraps_rows(&self) -> bool {
+        matches!(self, Topology::WrapRows | Topology::Torus)
+    }
+
+    pub fn wraps_columns(&self) -> bool {
+        matches!(self, Topology::WrapColumns | Topology::Torus)
+    }
+
+    /// Same joins once rows and columns are swapped
+    pub fn transposed(&self) -> Topology {
+        match self {
+            Topology::WrapColumns => Topology::WrapRows,
+            Topology::WrapRows => Topology::WrapColumns,
+            Topology::Bounded | Topology::Torus => *self,
+        }
+    }
+}
+
+/// Rectangular board, cells are stored row after row in a single vector
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(try_from = "RawGrid<T>")]
 pub struct Grid<T> {
-    grid: Vec<Vec<T>>,
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
     cell_type: CellType,
     cell_adjacency: CellAdjacency,
+    topology: Topology,
+}
+
+/// Deserialized grid, before checking its cells fill it
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawGrid<T> {
+    Flat {
+        rows: usize,
+        columns: usize,
+        cells: Vec<T>,
+        cell_type: CellType,
+        cell_adjacency: CellAdjacency,
+        #[serde(default)]
+        topology: Topology,
+    },
+    /// Grids serialized before the flat storage, with one vector per row
+    Nested {
+        grid: Vec<Vec<T>>,
+        cell_type: CellType,
+        cell_adjacency: CellAdjacency,
+        #[serde(default)]
+        topology: Topology,
+    },
+}
+
+impl<T> TryFrom<RawGrid<T>> for Grid<T> {
+    type Error = GridError;
+
+    fn try_from(raw: RawGrid<T>) -> Result<Self, Self::Error> {
+        let (grid, cell_type, cell_adjacency, topology) = match raw {
+            RawGrid::Flat {
+                rows,
+                columns,
+                cells,
+                cell_type,
+                cell_adjacency,
+                topology,
+            } => (
+                Self::from_cells(rows, columns, cells)?,
+                cell_type,
+                cell_adjacency,
+                topology,
+            ),
+            RawGrid::Nested {
+                grid,
+                cell_type,
+                cell_adjacency,
+                topology,
+            } => (
+                Self::try_from_rows(grid)?,
+                cell_type,
+                cell_adjacency,
+                topology,
+            ),
+        };
+
+        Ok(Self {
+            cell_type,
+            cell_adjacency,
+            topology,
+            ..grid
+        })
+    }
+}
+
+impl<T> TryFrom<Vec<Vec<T>>> for Grid<T> {
+    type Error = GridError;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self, Self::Error> {
+        Self::try_from_rows(rows)
+    }
 }
 
 impl<T: Clone> Grid<T> {
+    /// # Panics
+    ///
+    /// If rows have different lengths, see [`Grid::try_from_rows`]
     pub fn new_square_grid(grid: Vec<Vec<T>>) -> Self {
-        Self {
-            grid,
-            cell_type: CellType::Square,
-            cell_adjacency: CellAdjacency::Side,
-        }
+        Self::try_from_rows(grid).expect("rows of the same length")
     }
 
+    /// # Panics
+    ///
+    /// If rows have different lengths, see [`Grid::try_from_rows`]
     pub fn new_hex_grid(grid: Vec<Vec<T>>) -> Self {
         Self {
-            grid,
-            cell_type: CellType::Hex,
-            cell_adjacency: CellAdjacency::Side,
+            cell_type: CellType::Hex(HexLayout::default()),
+            ..Self::new_square_grid(grid)
         }
     }
 
@@ -84,67 +319,295 @@ impl<T: Clone> Grid<T> {
     }
 }
 
-impl<'a, T> GridExt<'a, T> for Grid<T> {
-    fn iter_row(&self, row: usize) -> RowCellIterator<T> {
-        self.grid.iter_row(row)
+impl<T> Grid<T> {
+    /// Square grid made of `rows`, which must all have the same length
+    pub fn try_from_rows(rows: Vec<Vec<T>>) -> Result<Self, GridError> {
+        let columns = rows.first().map_or(0, Vec::len);
+        if let Some((row, cells)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_, cells)| cells.len() != columns)
+        {
+            return Err(GridError::JaggedRow {
+                row,
+                len: cells.len(),
+                expected: columns,
+            });
+        }
+
+        Self::from_cells(rows.len(), columns, rows.into_iter().flatten().collect())
     }
-    fn iter_column(&self, col: usize) -> ColumnCellIterator<T> {
-        self.grid.iter_column(col)
+
+    /// Square grid of `cells` listed row after row
+    pub fn from_cells(rows: usize, columns: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if cells.len() != rows * columns {
+            return Err(GridError::CellCount {
+                rows,
+                columns,
+                len: cells.len(),
+            });
+        }
+
+        Ok(Self {
+            rows,
+            columns,
+            cells,
+            cell_type: CellType::Square,
+            cell_adjacency: CellAdjacency::Side,
+            topology: Topology::Bounded,
+        })
+    }
+
+    /// Grid with the same cell type and adjacency holding other cells
+    pub(super) fn with_cells<U>(&self, rows: usize, columns: usize, cells: Vec<U>) -> Grid<U> {
+        assert_eq!(cells.len(), rows * columns);
+        Grid {
+            rows,
+            columns,
+            cells,
+            cell_type: self.cell_type,
+            cell_adjacency: self.cell_adjacency,
+            topology: self.topology,
+        }
+    }
+
+    /// Same grid joining other edges
+    pub(super) fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    /// Index of `position` in [`Grid::as_slice`]
+    pub fn index(&self, position: &Position2d) -> Option<usize> {
+        (position.0 < self.rows && position.1 < self.columns)
+            .then(|| position.0 * self.columns + position.1)
+    }
+
+    /// Position of the cell at `index` in [`Grid::as_slice`]
+    pub fn position(&self, index: usize) -> Option<Position2d> {
+        (index < self.cells.len()).then(|| (index / self.columns, index % self.columns))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.rows).then(|| &self.cells[row * self.columns..(row + 1) * self.columns])
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> Option<&mut [T]> {
+        (row < self.rows).then(|| &mut self.cells[row * self.columns..(row + 1) * self.columns])
+    }
+
+    /// Every cell, row after row
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+
+    pub fn cell_type(&self) -> CellType {
+        self.cell_type
+    }
+
+    pub fn cell_adjacency(&self) -> CellAdjacency {
+        self.cell_adjacency
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Cell at a possibly out of board position, after wrapping the axes the topology joins
+    fn wrap(&self, row: i64, column: i64) -> Option<Position2d> {
+        fn axis(value: i64, len: usize, wraps: bool) -> Option<usize> {
+            if wraps && len > 0 {
+                Some(value.rem_euclid(len as i64) as usize)
+            } else {
+                (0..len as i64).contains(&value).then_some(value as usize)
+            }
+        }
+
+        let (wraps_rows, wraps_columns) = self.wrapped_axes();
+        Some((
+            axis(row, self.rows, wraps_rows)?,
+            axis(column, self.columns, wraps_columns)?,
+        ))
+    }
+
+    /// Whether rows and columns wrap, leaving out the axes whose odd length
+    /// would break the alternating hex or triangle layout across the seam
+    fn wrapped_axes(&self) -> (bool, bool) {
+        let (rows_alternate, columns_alternate) = match self.cell_type {
+            CellType::Square => (false, false),
+            CellType::Hex(HexLayout::OddR | HexLayout::EvenR) => (true, false),
+            CellType::Hex(HexLayout::OddQ | HexLayout::EvenQ) => (false, true),
+            CellType::Triangle => (true, true),
+        };
+
+        (
+            self.topology.wraps_rows() && !(rows_alternate && self.rows % 2 == 1),
+            self.topology.wraps_columns() && !(columns_alternate && self.columns % 2 == 1),
+        )
+    }
+
+    /// Axial coordinate of a cell of a hex grid
+    pub fn hex_coord(&self, position: &Position2d) -> Option<HexCoord> {
+        match self.cell_type {
+            CellType::Hex(layout) => Some(HexCoord::from_offset(position, layout)),
+            CellType::Square | CellType::Triangle => None,
+        }
+    }
+
+    /// Position of an axial coordinate of a hex grid, `None` outside the board
+    pub fn hex_position(&self, coord: &HexCoord) -> Option<Position2d> {
+        match self.cell_type {
+            CellType::Hex(layout) => coord
+                .to_offset(layout)
+                .filter(|p| p.0 < self.row_len() && p.1 < self.column_len()),
+            CellType::Square | CellType::Triangle => None,
+        }
+    }
+
+    /// Number of hexagons between two cells of a hex grid
+    pub fn hex_distance(&self, from: &Position2d, to: &Position2d) -> Option<usize> {
+        Some(self.hex_coord(from)?.distance(&self.hex_coord(to)?))
+    }
+
+    /// Coordinate of a cell of a triangle grid
+    pub fn tri_coord(&self, position: &Position2d) -> Option<TriCoord> {
+        (self.cell_type == CellType::Triangle).then(|| TriCoord::from_offset(position))
+    }
+
+    /// Position of a coordinate of a triangle grid, `None` outside the board
+    pub fn tri_position(&self, coord: &TriCoord) -> Option<Position2d> {
+        (self.cell_type == CellType::Triangle)
+            .then(|| coord.to_offset())?
+            .filter(|p| p.0 < self.row_len() && p.1 < self.column_len())
+    }
+
+    /// Number of steps between two cells of a triangle grid, following its cell adjacency
+    pub fn tri_distance(&self, from: &Position2d, to: &Position2d) -> Option<usize> {
+        let (from, to) = (self.tri_coord(from)?, self.tri_coord(to)?);
+        Some(match self.cell_adjacency {
+            CellAdjacency::Side => from.distance(&to),
+            CellAdjacency::SideAndVertex => from.vertex_distance(&to),
+        })
+    }
+
+    /// One direction per axis along which cells form straight lines.
+    ///
+    /// Hex directions are axial offsets, use [`Grid::step`] to follow them.
+    /// Triangle grids only have rows, other lines zigzag between up and down triangles.
+    pub fn line_directions(&self) -> Vec<Position2dDiff> {
+        match (&self.cell_type, &self.cell_adjacency) {
+            (CellType::Square, CellAdjacency::Side) => vec![(0, 1), (1, 0)],
+            (CellType::Square, CellAdjacency::SideAndVertex) => {
+                vec![(0, 1), (1, 0), (1, 1), (1, -1)]
+            }
+            (CellType::Hex(_), _) => vec![(1, 0), (0, 1), (1, -1)],
+            (CellType::Triangle, _) => vec![(0, 1)],
+        }
+    }
+
+    /// Cell one step away from `position` in `direction`, `None` outside the board
+    pub fn step(&self, position: &Position2d, direction: Position2dDiff) -> Option<Position2d> {
+        let (row, column) = match self.cell_type {
+            CellType::Square | CellType::Triangle => (
+                position.0 as i64 + direction.0 as i64,
+                position.1 as i64 + direction.1 as i64,
+            ),
+            CellType::Hex(layout) => {
+                let (row, column) =
+                    (HexCoord::from_offset(position, layout) + direction).to_signed_offset(layout);
+                (row as i64, column as i64)
+            }
+        };
+
+        self.wrap(row, column)
+    }
+}
+
+impl<'a, T: 'a> GridExt<'a, T> for Grid<T> {
+    type RowCells = RowCellIterator<'a, T>;
+    type Rows = ChunksExact<'a, T>;
+
+    /// Empty when `row` is outside the board
+    fn iter_row(&'a self, row: usize) -> Self::RowCells {
+        self.row(row).unwrap_or_default().iter()
+    }
+    fn iter_column(&'a self, col: usize) -> ColumnCellIterator<'a, T> {
+        ColumnCellIterator::new(self, col)
     }
 
     fn row_len(&self) -> usize {
-        self.grid.row_len()
+        self.rows
     }
     fn column_len(&self) -> usize {
-        self.grid.column_len()
+        self.columns
     }
 
-    fn rows(&self) -> RowIterator<T> {
-        self.grid.rows()
+    /// Grids without columns have no cells to split into rows, they yield none
+    fn rows(&'a self) -> Self::Rows {
+        self.cells.chunks_exact(self.columns.max(1))
     }
-    fn columns(&self) -> ColumnIterator<T> {
-        self.grid.columns()
+    fn columns(&'a self) -> ColumnIterator<'a, T> {
+        ColumnIterator::new(self)
     }
 
     fn get(&'a self, pos: &Position2d) -> Option<&'a T> {
-        self.grid.get(pos)
-    }
-    fn get_mut(&'a mut self, pos: &Position2d) -> Option<&'a mut T> {
-        self.grid.get_mut(pos)
+        self.cells.get(self.index(pos)?)
     }
 
-    fn adjacencies(&self) -> Vec<Position2dDiff> {
-        match (&self.cell_type, &self.cell_adjacency) {
-            #[rustfmt::skip]
-            (CellType::Square, CellAdjacency::Side) => vec![
-                        (-1, 0),
-                (0, -1),         (0, 1),
-                        ( 1, 0)],
-            #[rustfmt::skip]
-            (CellType::Square, CellAdjacency::SideAndVertex) => vec![
-                (-1, -1), (-1, 0), (-1, 1),
-                ( 0, -1),          ( 0, 1),
-                ( 1, -1), ( 1, 0), ( 1, 1),
-            ],
-            (CellType::Hex, CellAdjacency::Side) => todo!(),
-            (CellType::Hex, CellAdjacency::SideAndVertex) => todo!(),
+    fn adjacencies(&self, position: &Position2d) -> Vec<Position2dDiff> {
+        match &self.cell_type {
+            CellType::Square => self.cell_adjacency.square_offsets(),
+            CellType::Hex(layout) => HexCoord::from_offset(position, *layout)
+                .neighbors()
+                .iter()
+                .map(|neighbor| neighbor.to_signed_offset(*layout))
+                .map(|p| (p.0 - position.0 as i32, p.1 - position.1 as i32))
+                .collect(),
+            CellType::Triangle => TriCoord::from_offset(position).adjacencies(self.cell_adjacency),
         }
     }
 
     fn adjacent_cells(&'a self, current: &Position2d) -> Vec<(Position2d, &'a T)> {
-        self.adjacencies()
-            .into_iter()
-            .filter_map(|diff| current.add(diff))
-            .filter_map(|p| Some((p, self.get(&p)?)))
-            .collect()
+        let mut cells: Vec<(Position2d, &'a T)> = vec![];
+        for diff in self.adjacencies(current) {
+            let Some(p) = self.wrap(
+                current.0 as i64 + diff.0 as i64,
+                current.1 as i64 + diff.1 as i64,
+            ) else {
+                continue;
+            };
+
+            // narrow wrapped boards reach the same cell through several sides
+            if p != *current && cells.iter().all(|(q, _)| *q != p) {
+                cells.push((p, self.get(&p).expect("inside the board")));
+            }
+        }
+        cells
     }
 }
 
-impl<'a, T> GridExt<'a, T> for Vec<Vec<T>> {
-    fn iter_row(&self, row: usize) -> RowCellIterator<T> {
+impl<'a, T: 'a> GridExtMut<'a, T> for Grid<T> {
+    fn get_mut(&'a mut self, pos: &Position2d) -> Option<&'a mut T> {
+        let index = self.index(pos)?;
+        self.cells.get_mut(index)
+    }
+}
+
+impl<'a, T: 'a> GridExt<'a, T> for Vec<Vec<T>> {
+    type RowCells = RowCellIterator<'a, T>;
+    type Rows = RowIterator<'a, T>;
+
+    fn iter_row(&'a self, row: usize) -> Self::RowCells {
         self[row].iter()
     }
-    fn iter_column(&self, col: usize) -> ColumnCellIterator<T> {
+    fn iter_column(&'a self, col: usize) -> ColumnCellIterator<'a, T> {
         ColumnCellIterator::new(self, col)
     }
 
@@ -159,26 +622,23 @@ impl<'a, T> GridExt<'a, T> for Vec<Vec<T>> {
         self[0].len()
     }
 
-    fn rows(&self) -> RowIterator<T> {
+    fn rows(&'a self) -> Self::Rows {
         self.iter()
     }
-    fn columns(&self) -> ColumnIterator<T> {
+    fn columns(&'a self) -> ColumnIterator<'a, T> {
         ColumnIterator::new(self)
     }
 
     fn get(&'a self, pos: &Position2d) -> Option<&'a T> {
         self.deref().get(pos.0)?.get(pos.1)
     }
-    fn get_mut(&'a mut self, pos: &Position2d) -> Option<&'a mut T> {
-        self.deref_mut().get_mut(pos.0)?.get_mut(pos.1)
-    }
 
-    fn adjacencies(&self) -> Vec<Position2dDiff> {
+    fn adjacencies(&self, _position: &Position2d) -> Vec<Position2dDiff> {
         vec![(0, -1), (0, 1), (-1, 0), (1, 0)]
     }
 
     fn adjacent_cells(&'a self, current: &Position2d) -> Vec<(Position2d, &'a T)> {
-        self.adjacencies()
+        self.adjacencies(current)
             .into_iter()
             .filter_map(|diff| current.add(diff))
             .filter_map(|p| Some((p, self.get(&p)?)))
@@ -186,6 +646,12 @@ impl<'a, T> GridExt<'a, T> for Vec<Vec<T>> {
     }
 }
 
+impl<'a, T: 'a> GridExtMut<'a, T> for Vec<Vec<T>> {
+    fn get_mut(&'a mut self, pos: &Position2d) -> Option<&'a mut T> {
+        self.deref_mut().get_mut(pos.0)?.get_mut(pos.1)
+    }
+}
+
 #[derive(Debug)]
 pub struct GridBuilder<T: Clone> {
     rows: Option<usize>,
@@ -193,6 +659,7 @@ pub struct GridBuilder<T: Clone> {
     initial_value: Option<T>,
     cell_type: Option<CellType>,
     cell_adjacency: Option<CellAdjacency>,
+    topology: Option<Topology>,
 }
 
 impl<T: Clone> GridBuilder<T> {
@@ -203,6 +670,7 @@ impl<T: Clone> GridBuilder<T> {
             initial_value: None,
             cell_type: None,
             cell_adjacency: None,
+            topology: None,
         }
     }
 
@@ -213,19 +681,37 @@ impl<T: Clone> GridBuilder<T> {
             initial_value: None,
             cell_type: Some(CellType::Square),
             cell_adjacency: Some(CellAdjacency::Side),
+            topology: None,
         }
     }
 
-    pub fn new_hey_grid() -> Self {
+    pub fn new_hex_grid() -> Self {
         Self {
             rows: None,
             columns: None,
             initial_value: None,
-            cell_type: Some(CellType::Hex),
+            cell_type: Some(CellType::Hex(HexLayout::default())),
             cell_adjacency: Some(CellAdjacency::Side),
+            topology: None,
         }
     }
 
+    pub fn new_triangle_grid() -> Self {
+        Self {
+            rows: None,
+            columns: None,
+            initial_value: None,
+            cell_type: Some(CellType::Triangle),
+            cell_adjacency: Some(CellAdjacency::Side),
+            topology: None,
+        }
+    }
+
+    #[deprecated(note = "use `new_hex_grid`")]
+    pub fn new_hey_grid() -> Self {
+        Self::new_hex_grid()
+    }
+
     pub fn with_rows(&mut self, rows: usize) -> &mut Self {
         self.rows = Some(rows);
         self
@@ -251,23 +737,28 @@ impl<T: Clone> GridBuilder<T> {
         self
     }
 
+    /// Defaults to [`Topology::Bounded`]
+    pub fn with_topology(&mut self, topology: Topology) -> &mut Self {
+        self.topology = Some(topology);
+        self
+    }
+
     pub fn build(&self) -> Grid<T> {
         let rows = self.rows.unwrap();
         let columns = self.columns.unwrap();
         let initial_value = self.initial_value.clone().unwrap();
 
-        let grid = std::iter::repeat_with(|| {
-            std::iter::repeat_with(|| initial_value.clone())
-                .take(columns)
-                .collect()
-        })
-        .take(rows)
-        .collect();
+        let cells = std::iter::repeat_with(|| initial_value.clone())
+            .take(rows * columns)
+            .collect();
 
         Grid {
-            grid,
+            rows,
+            columns,
+            cells,
             cell_type: self.cell_type.unwrap(),
             cell_adjacency: self.cell_adjacency.unwrap(),
+            topology: self.topology.unwrap_or_default(),
         }
     }
 }
@@ -280,28 +771,24 @@ impl<T: Clone> Default for GridBuilder<T> {
 
 pub struct ColumnIterator<'a, T: 'a> {
     board: &'a dyn GridExt<'a, T>,
-    row: usize,
+    col: usize,
 }
 
 impl<'a, T> ColumnIterator<'a, T> {
     pub fn new(board: &'a dyn GridExt<'a, T>) -> Self {
-        Self { board, row: 0 }
+        Self { board, col: 0 }
     }
 }
 
 impl<'a, T> Iterator for ColumnIterator<'a, T> {
     type Item = Vec<&'a T>;
     fn next(&mut self) -> Option<Self::Item> {
-        let mut result = Vec::with_capacity(self.board.column_len());
-        for col in 0..self.board.column_len() {
-            result.push(
-                self.board
-                    .get(&(self.row, col))
-                    .expect("inside column range"),
-            );
+        if self.col >= self.board.column_len() {
+            return None;
         }
 
-        self.row += 1;
+        let result = self.board.iter_column(self.col).collect();
+        self.col += 1;
         Some(result)
     }
 }
@@ -326,3 +813,322 @@ impl<'a, T> Iterator for ColumnCellIterator<'a, T> {
         result
     }
 }
+
+pub struct DiagonalIterator<'a, T: 'a> {
+    board: &'a dyn GridExt<'a, T>,
+    anti: bool,
+    diagonal: usize,
+}
+
+impl<'a, T> DiagonalIterator<'a, T> {
+    pub fn new(board: &'a dyn GridExt<'a, T>) -> Self {
+        Self {
+            board,
+            anti: false,
+            diagonal: 0,
+        }
+    }
+
+    pub fn new_anti(board: &'a dyn GridExt<'a, T>) -> Self {
+        Self {
+            board,
+            anti: true,
+            diagonal: 0,
+        }
+    }
+}
+
+impl<'a, T> Iterator for DiagonalIterator<'a, T> {
+    type Item = Vec<&'a T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (rows, columns) = (self.board.row_len(), self.board.column_len());
+        if rows == 0 || columns == 0 || self.diagonal >= rows + columns - 1 {
+            return None;
+        }
+
+        // diagonals start on the left or top edge, anti-diagonals on the top or right one
+        let d = self.diagonal;
+        let start = match (self.anti, d < rows) {
+            (false, true) => (rows - 1 - d, 0),
+            (false, false) => (0, d + 1 - rows),
+            (true, _) if d < columns => (0, d),
+            (true, _) => (d + 1 - columns, columns - 1),
+        };
+
+        self.diagonal += 1;
+        Some(
+            DiagonalCellIterator {
+                board: self.board,
+                anti: self.anti,
+                position: Some(start),
+            }
+            .collect(),
+        )
+    }
+}
+
+pub struct DiagonalCellIterator<'a, T> {
+    board: &'a dyn GridExt<'a, T>,
+    anti: bool,
+    position: Option<Position2d>,
+}
+
+impl<'a, T> DiagonalCellIterator<'a, T> {
+    /// Empty when `position` is outside the board
+    pub fn through(board: &'a dyn GridExt<'a, T>, position: &Position2d) -> Self {
+        Self {
+            board,
+            anti: false,
+            position: board.get(position).map(|_| {
+                let back = position.0.min(position.1);
+                (position.0 - back, position.1 - back)
+            }),
+        }
+    }
+
+    /// Empty when `position` is outside the board
+    pub fn anti_through(board: &'a dyn GridExt<'a, T>, position: &Position2d) -> Self {
+        Self {
+            board,
+            anti: true,
+            position: board.get(position).and_then(|_| {
+                // other implementors may hold cells past the column length
+                let back = position
+                    .0
+                    .min(board.column_len().checked_sub(1 + position.1)?);
+                Some((position.0 - back, position.1 + back))
+            }),
+        }
+    }
+}
+
+impl<'a, T> Iterator for DiagonalCellIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (row, col) = self.position?;
+        let Some(result) = self.board.get(&(row, col)) else {
+            self.position = None;
+            return None;
+        };
+
+        self.position = if self.anti {
+            col.checked_sub(1).map(|col| (row + 1, col))
+        } else {
+            Some((row + 1, col + 1))
+        };
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::line::LineDetection;
+    use crate::board::ray::RayCasting;
+
+    #[test]
+    fn rejects_jagged_rows() {
+        assert_eq!(
+            Grid::try_from_rows(vec![vec![1, 2], vec![3]]),
+            Err(GridError::JaggedRow {
+                row: 1,
+                len: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            Grid::from_cells(2, 2, vec![1, 2, 3]),
+            Err(GridError::CellCount {
+                rows: 2,
+                columns: 2,
+                len: 3
+            })
+        );
+        assert!(Grid::<u8>::try_from(vec![]).is_ok());
+    }
+
+    #[test]
+    fn flat_storage() {
+        let mut grid = Grid::new_square_grid(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        assert_eq!(grid.as_slice(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(grid.index(&(1, 0)), Some(3));
+        assert_eq!(grid.index(&(0, 3)), None);
+        assert_eq!(grid.position(5), Some((1, 2)));
+        assert_eq!(grid.position(6), None);
+
+        assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.row(2), None);
+        grid.row_mut(0).unwrap()[2] = 9;
+        assert_eq!(grid.get(&(0, 2)), Some(&9));
+    }
+
+    fn text<'a, L: IntoIterator<Item = &'a char>>(lines: impl Iterator<Item = L>) -> Vec<String> {
+        lines.map(|line| line.into_iter().collect()).collect()
+    }
+
+    #[test]
+    fn columns() {
+        let grid = Grid::new_square_grid(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]);
+
+        assert_eq!(text(grid.columns()), vec!["ad", "be", "cf"]);
+        assert_eq!(text(grid.rows()), vec!["abc", "def"]);
+        assert_eq!(vec![vec![1, 2], vec![3, 4]].columns().count(), 2);
+    }
+
+    #[test]
+    fn diagonals() {
+        let grid = Grid::new_square_grid(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]);
+
+        assert_eq!(text(grid.diagonals()), vec!["d", "ae", "bf", "c"]);
+        assert_eq!(text(grid.anti_diagonals()), vec!["a", "bd", "ce", "f"]);
+        assert_eq!(Grid::<u8>::try_from(vec![]).unwrap().diagonals().count(), 0);
+    }
+
+    #[test]
+    fn diagonal_through() {
+        let grid = Grid::new_square_grid(vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]);
+
+        assert_eq!(grid.diagonal_through(&(1, 2)).collect::<String>(), "bf");
+        assert_eq!(grid.diagonal_through(&(0, 0)).collect::<String>(), "ae");
+        assert_eq!(
+            grid.anti_diagonal_through(&(1, 1)).collect::<String>(),
+            "ce"
+        );
+        assert_eq!(grid.anti_diagonal_through(&(0, 0)).collect::<String>(), "a");
+        assert_eq!(grid.diagonal_through(&(2, 2)).count(), 0);
+        assert_eq!(grid.anti_diagonal_through(&(0, 3)).count(), 0);
+    }
+
+    fn wrapped(topology: Topology) -> Grid<()> {
+        GridBuilder::new_square_grid()
+            .with_rows(3)
+            .with_columns(4)
+            .with_initial_value(())
+            .with_topology(topology)
+            .build()
+    }
+
+    fn neighbors(grid: &Grid<()>, position: Position2d) -> Vec<Position2d> {
+        let mut neighbors: Vec<_> = grid
+            .adjacent_cells(&position)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn torus_adjacency() {
+        let grid = wrapped(Topology::Torus);
+
+        assert_eq!(
+            neighbors(&grid, (0, 0)),
+            vec![(0, 1), (0, 3), (1, 0), (2, 0)]
+        );
+        assert_eq!(grid.step(&(2, 3), (1, 1)), Some((0, 0)));
+    }
+
+    #[test]
+    fn cylinder_adjacency() {
+        let grid = wrapped(Topology::WrapColumns);
+        assert_eq!(neighbors(&grid, (0, 0)), vec![(0, 1), (0, 3), (1, 0)]);
+        assert_eq!(grid.step(&(0, 0), (-1, 0)), None);
+
+        let grid = wrapped(Topology::WrapRows);
+        assert_eq!(neighbors(&grid, (0, 0)), vec![(0, 1), (1, 0), (2, 0)]);
+        assert_eq!(grid.step(&(0, 3), (0, 1)), None);
+    }
+
+    #[test]
+    fn narrow_torus() {
+        let grid: Grid<()> = GridBuilder::new_square_grid()
+            .with_rows(1)
+            .with_columns(2)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+
+        assert_eq!(neighbors(&grid, (0, 0)), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn wrapped_hex_adjacency() {
+        let grid: Grid<()> = GridBuilder::new_hex_grid()
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+
+        // odd-r, the row above the first one is the shoved right row 3
+        assert_eq!(
+            neighbors(&grid, (0, 0)),
+            vec![(0, 1), (0, 3), (1, 0), (1, 3), (3, 0), (3, 3)]
+        );
+        for row in 0..4 {
+            for col in 0..4 {
+                for neighbor in neighbors(&grid, (row, col)) {
+                    assert!(neighbors(&grid, neighbor).contains(&(row, col)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn odd_wrapped_hex_axis() {
+        let grid: Grid<()> = GridBuilder::new_hex_grid()
+            .with_rows(3)
+            .with_columns(4)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+
+        // odd-r rows can't wrap with 3 rows, columns still do
+        assert_eq!(grid.step(&(0, 0), (0, -1)), None);
+        assert_eq!(grid.step(&(0, 0), (-1, 0)), Some((0, 3)));
+        for direction in grid.line_directions() {
+            for row in 0..3 {
+                for col in 0..4 {
+                    assert!(grid.ray(&(row, col), direction).count() < 12);
+                }
+            }
+        }
+        assert!(!grid.lines(2).is_empty());
+    }
+
+    #[test]
+    fn serde_checks_shape() {
+        let grid = Grid::new_hex_grid(vec![vec![1, 2], vec![3, 4]]);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid<u8>>(&json).unwrap(), grid);
+
+        let json = json.replace("[1,2,3,4]", "[1,2,3]");
+        assert!(serde_json::from_str::<Grid<u8>>(&json).is_err());
+    }
+
+    #[test]
+    fn serde_reads_nested_rows() {
+        let json = r#"{"grid":[[1,2],[3,4]],"cell_type":"Square","cell_adjacency":"Side"}"#;
+        assert_eq!(
+            serde_json::from_str::<Grid<u8>>(json).unwrap(),
+            Grid::new_square_grid(vec![vec![1, 2], vec![3, 4]])
+        );
+
+        let json = json.replace("[3,4]", "[3]");
+        assert!(serde_json::from_str::<Grid<u8>>(&json).is_err());
+    }
+
+    #[test]
+    fn serde_reads_bare_hex() {
+        let json = r#"{"grid":[[1,2],[3,4]],"cell_type":"Hex","cell_adjacency":"Side"}"#;
+        let grid = serde_json::from_str::<Grid<u8>>(json).unwrap();
+        assert_eq!(grid, Grid::new_hex_grid(vec![vec![1, 2], vec![3, 4]]));
+        assert_eq!(grid.cell_type(), CellType::Hex(HexLayout::OddR));
+
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid<u8>>(&json).unwrap(), grid);
+        assert!(serde_json::from_str::<CellType>(r#""Circle""#).is_err());
+    }
+}
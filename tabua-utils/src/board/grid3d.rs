@@ -0,0 +1,442 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::grid::{Grid, GridExt};
+use super::line::{self, Line, LineDetection, Reverse};
+use super::neighbors::Neighbors;
+
+/// `(layer, row, column)` position
+pub type Position3d = (usize, usize, usize);
+pub type Position3dDiff = (i32, i32, i32);
+
+/// Which cubes touching a cube are adjacent to it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Adjacency3d {
+    /// The 6 cubes sharing a face
+    Face,
+    /// The 18 cubes sharing a face or an edge
+    FaceAndEdge,
+    /// All 26 cubes sharing a face, an edge or a vertex
+    FaceEdgeAndVertex,
+}
+
+impl Adjacency3d {
+    /// Offsets of the cells adjacent to any cell
+    pub fn offsets(&self) -> Vec<Position3dDiff> {
+        let max_axes = match self {
+            Adjacency3d::Face => 1,
+            Adjacency3d::FaceAndEdge => 2,
+            Adjacency3d::FaceEdgeAndVertex => 3,
+        };
+
+        (-1..=1)
+            .flat_map(|l| (-1..=1).flat_map(move |r| (-1..=1).map(move |c| (l, r, c))))
+            .filter(|&(l, r, c)| {
+                let axes = [l, r, c].iter().filter(|step| **step != 0).count();
+                (1..=max_axes).contains(&axes)
+            })
+            .collect()
+    }
+
+    /// One direction per axis along which cells form straight lines
+    pub fn line_directions(&self) -> Vec<Position3dDiff> {
+        self.offsets()
+            .into_iter()
+            .filter(|&(l, r, c)| [l, r, c].into_iter().find(|step| *step != 0) == Some(1))
+            .collect()
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Grid3dError {
+    #[error("{len} cells cannot fill a {layers}x{rows}x{columns} grid")]
+    CellCount {
+        layers: usize,
+        rows: usize,
+        columns: usize,
+        len: usize,
+    },
+    #[error("Layer {layer} is {rows}x{columns}, expected {expected_rows}x{expected_columns}")]
+    LayerShape {
+        layer: usize,
+        rows: usize,
+        columns: usize,
+        expected_rows: usize,
+        expected_columns: usize,
+    },
+}
+
+/// Box of cubic cells, like a 3D tic-tac-toe board or the floors of a dungeon.
+///
+/// Cells are stored layer after layer, each layer row after row.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(try_from = "RawGrid3d<T>")]
+pub struct Grid3d<T> {
+    layers: usize,
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+    adjacency: Adjacency3d,
+}
+
+/// Deserialized grid, before checking its cells fill it
+#[derive(Deserialize)]
+struct RawGrid3d<T> {
+    layers: usize,
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+    adjacency: Adjacency3d,
+}
+
+impl<T> TryFrom<RawGrid3d<T>> for Grid3d<T> {
+    type Error = Grid3dError;
+
+    fn try_from(raw: RawGrid3d<T>) -> Result<Self, Self::Error> {
+        Ok(
+            Self::from_cells(raw.layers, raw.rows, raw.columns, raw.cells)?
+                .with_adjacency(raw.adjacency),
+        )
+    }
+}
+
+impl<T: Clone> Grid3d<T> {
+    pub fn new(layers: usize, rows: usize, columns: usize, initial_value: T) -> Self {
+        Self {
+            layers,
+            rows,
+            columns,
+            cells: vec![initial_value; layers * rows * columns],
+            adjacency: Adjacency3d::Face,
+        }
+    }
+}
+
+impl<T> Grid3d<T> {
+    /// Grid of `cells` listed layer after layer, with face adjacency
+    pub fn from_cells(
+        layers: usize,
+        rows: usize,
+        columns: usize,
+        cells: Vec<T>,
+    ) -> Result<Self, Grid3dError> {
+        if cells.len() != layers * rows * columns {
+            return Err(Grid3dError::CellCount {
+                layers,
+                rows,
+                columns,
+                len: cells.len(),
+            });
+        }
+
+        Ok(Self {
+            layers,
+            rows,
+            columns,
+            cells,
+            adjacency: Adjacency3d::Face,
+        })
+    }
+
+    /// Stack of 2D grids of the same size, the first one is layer 0
+    pub fn from_layers(layers: Vec<Grid<T>>) -> Result<Self, Grid3dError> {
+        let (rows, columns) = layers
+            .first()
+            .map_or((0, 0), |layer| (layer.row_len(), layer.column_len()));
+        if let Some((layer, grid)) = layers
+            .iter()
+            .enumerate()
+            .find(|(_, grid)| grid.row_len() != rows || grid.column_len() != columns)
+        {
+            return Err(Grid3dError::LayerShape {
+                layer,
+                rows: grid.row_len(),
+                columns: grid.column_len(),
+                expected_rows: rows,
+                expected_columns: columns,
+            });
+        }
+
+        let count = layers.len();
+        let cells = layers.into_iter().flat_map(Grid::into_cells).collect();
+        Self::from_cells(count, rows, columns, cells)
+    }
+
+    pub fn with_adjacency(mut self, adjacency: Adjacency3d) -> Self {
+        self.adjacency = adjacency;
+        self
+    }
+
+    pub fn adjacency(&self) -> Adjacency3d {
+        self.adjacency
+    }
+
+    pub fn layer_len(&self) -> usize {
+        self.layers
+    }
+
+    pub fn row_len(&self) -> usize {
+        self.rows
+    }
+
+    pub fn column_len(&self) -> usize {
+        self.columns
+    }
+
+    /// Index of `position` in [`Grid3d::as_slice`]
+    pub fn index(&self, position: &Position3d) -> Option<usize> {
+        let (layer, row, column) = *position;
+        (layer < self.layers && row < self.rows && column < self.columns)
+            .then(|| (layer * self.rows + row) * self.columns + column)
+    }
+
+    /// Position of the cell at `index` in [`Grid3d::as_slice`]
+    pub fn position(&self, index: usize) -> Option<Position3d> {
+        (index < self.cells.len()).then(|| {
+            let layer_size = self.rows * self.columns;
+            (
+                index / layer_size,
+                index % layer_size / self.columns,
+                index % self.columns,
+            )
+        })
+    }
+
+    pub fn get(&self, position: &Position3d) -> Option<&T> {
+        self.cells.get(self.index(position)?)
+    }
+
+    pub fn get_mut(&mut self, position: &Position3d) -> Option<&mut T> {
+        let index = self.index(position)?;
+        self.cells.get_mut(index)
+    }
+
+    /// Cells of a layer, row after row
+    pub fn layer(&self, layer: usize) -> Option<&[T]> {
+        let size = self.rows * self.columns;
+        (layer < self.layers).then(|| &self.cells[layer * size..(layer + 1) * size])
+    }
+
+    /// Every cell, layer after layer
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+
+    /// Every cell with its position, layer after layer
+    pub fn iter(&self) -> impl Iterator<Item = (Position3d, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (self.position(index).expect("inside the board"), cell))
+    }
+
+    /// One direction per axis along which cells form straight lines
+    pub fn line_directions(&self) -> Vec<Position3dDiff> {
+        self.adjacency.line_directions()
+    }
+
+    /// Cell one step away from `position` in `direction`, `None` outside the board
+    pub fn step(&self, position: &Position3d, direction: Position3dDiff) -> Option<Position3d> {
+        let position = (
+            position.0.checked_add_signed(direction.0 as isize)?,
+            position.1.checked_add_signed(direction.1 as isize)?,
+            position.2.checked_add_signed(direction.2 as isize)?,
+        );
+        self.index(&position).map(|_| position)
+    }
+
+    pub fn adjacent_cells(&self, current: &Position3d) -> Vec<(Position3d, &T)> {
+        self.adjacency
+            .offsets()
+            .into_iter()
+            .filter_map(|diff| self.step(current, diff))
+            .map(|p| (p, self.get(&p).expect("inside the board")))
+            .collect()
+    }
+
+    fn runs<A, S>(
+        &self,
+        directions: &[Position3dDiff],
+        min_length: usize,
+        accept: A,
+        same: S,
+    ) -> Vec<Line<Position3d, Position3dDiff>>
+    where
+        A: Fn(&T) -> bool,
+        S: Fn(&T, &T) -> bool,
+    {
+        line::runs(
+            (0..self.cells.len()).map(|index| self.position(index).expect("inside the board")),
+            directions,
+            min_length,
+            |position| self.get(position),
+            |position, direction| self.step(position, direction),
+            accept,
+            same,
+        )
+    }
+}
+
+impl Reverse for Position3dDiff {
+    fn reverse(self) -> Self {
+        (-self.0, -self.1, -self.2)
+    }
+}
+
+impl<T> LineDetection<T> for Grid3d<T> {
+    type Position = Position3d;
+    type Direction = Position3dDiff;
+
+    fn lines(&self, min_length: usize) -> Vec<Line<Position3d, Position3dDiff>>
+    where
+        T: PartialEq,
+    {
+        self.runs(&self.line_directions(), min_length, |_| true, |a, b| a == b)
+    }
+
+    fn lines_matching<F>(
+        &self,
+        min_length: usize,
+        predicate: F,
+    ) -> Vec<Line<Position3d, Position3dDiff>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.runs(&self.line_directions(), min_length, predicate, |_, _| true)
+    }
+
+    fn lines_along<F>(
+        &self,
+        directions: &[Position3dDiff],
+        min_length: usize,
+        predicate: F,
+    ) -> Vec<Line<Position3d, Position3dDiff>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.runs(directions, min_length, predicate, |_, _| true)
+    }
+}
+
+impl<T> Neighbors for Grid3d<T> {
+    type Position = Position3d;
+    type Cell = T;
+
+    fn cell(&self, position: &Position3d) -> Option<&T> {
+        self.get(position)
+    }
+
+    fn neighbors(&self, position: &Position3d) -> Vec<(Position3d, &T)> {
+        self.adjacent_cells(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinder::bfs::BreadthFirstSearch;
+
+    #[test]
+    fn adjacency() {
+        let grid = Grid3d::new(3, 3, 3, ());
+
+        for (adjacency, count, directions) in [
+            (Adjacency3d::Face, 6, 3),
+            (Adjacency3d::FaceAndEdge, 18, 9),
+            (Adjacency3d::FaceEdgeAndVertex, 26, 13),
+        ] {
+            let grid = grid.clone().with_adjacency(adjacency);
+            assert_eq!(grid.adjacent_cells(&(1, 1, 1)).len(), count);
+            assert_eq!(grid.line_directions().len(), directions);
+        }
+
+        let corner = grid.with_adjacency(Adjacency3d::FaceEdgeAndVertex);
+        assert_eq!(corner.adjacent_cells(&(0, 0, 0)).len(), 7);
+    }
+
+    #[test]
+    fn storage() {
+        let mut grid = Grid3d::new(2, 3, 4, 0);
+        *grid.get_mut(&(1, 2, 3)).unwrap() = 7;
+
+        assert_eq!(grid.index(&(1, 2, 3)), Some(23));
+        assert_eq!(grid.position(23), Some((1, 2, 3)));
+        assert_eq!(grid.layer(1).unwrap()[11], 7);
+        assert_eq!(grid.get(&(2, 0, 0)), None);
+        assert_eq!(grid.position(24), None);
+    }
+
+    #[test]
+    fn from_layers() {
+        let floor = Grid::new_square_grid(vec![vec!['.', '#'], vec!['.', '.']]);
+        let grid = Grid3d::from_layers(vec![floor.clone(), floor.clone()]).unwrap();
+        assert_eq!(grid.get(&(1, 0, 1)), Some(&'#'));
+
+        let small = Grid::new_square_grid(vec![vec!['.']]);
+        assert_eq!(
+            Grid3d::from_layers(vec![floor, small]),
+            Err(Grid3dError::LayerShape {
+                layer: 1,
+                rows: 1,
+                columns: 1,
+                expected_rows: 2,
+                expected_columns: 2
+            })
+        );
+    }
+
+    #[test]
+    fn qubic_lines() {
+        let grid = Grid3d::new(4, 4, 4, 'x').with_adjacency(Adjacency3d::FaceEdgeAndVertex);
+        assert_eq!(grid.lines_matching(4, |_| true).len(), 76);
+
+        let mut grid = Grid3d::new(4, 4, 4, '.').with_adjacency(Adjacency3d::FaceEdgeAndVertex);
+        for i in 0..4 {
+            *grid.get_mut(&(i, i, 3 - i)).unwrap() = 'x';
+        }
+        let lines = grid.lines_matching(4, |cell| *cell == 'x');
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].direction, (1, 1, -1));
+        assert_eq!((lines[0].start(), lines[0].end()), ((0, 0, 3), (3, 3, 0)));
+    }
+
+    #[test]
+    fn dungeon_floors() {
+        // two corridors, the solid floor between them has a stairwell at its end
+        let grid = Grid3d::from_cells(3, 1, 3, "...##s...".chars().collect()).unwrap();
+
+        let path = grid
+            .bfs_with(&(0, 0, 0), &(2, 0, 0), |_, cell| *cell != '#')
+            .unwrap();
+        assert_eq!(
+            path,
+            vec![
+                (0, 0, 0),
+                (0, 0, 1),
+                (0, 0, 2),
+                (1, 0, 2),
+                (2, 0, 2),
+                (2, 0, 1),
+                (2, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let grid = Grid3d::new(2, 2, 2, 1).with_adjacency(Adjacency3d::FaceAndEdge);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(serde_json::from_str::<Grid3d<i32>>(&json).unwrap(), grid);
+
+        let json = r#"{ "layers": 2, "rows": 2, "columns": 2, "cells": [1], "adjacency": "Face" }"#;
+        assert!(serde_json::from_str::<Grid3d<i32>>(json).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::ops::Add;
+
+use serde::{Deserialize, Serialize};
+
+use super::grid::{Position2d, Position2dDiff};
+
+/// How hexagons of a [`Grid`](super::grid::Grid) are laid out in rows and columns.
+///
+/// `OddR`/`EvenR` are pointy topped with odd/even rows shoved right,
+/// `OddQ`/`EvenQ` are flat topped with odd/even columns shoved down.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HexLayout {
+    #[default]
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+/// Axial hex coordinate, the cube coordinate `s` is implied by `q + r + s = 0`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct HexCoord {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexCoord {
+    /// Axial offsets of the six neighbors
+    #[rustfmt::skip]
+    pub const DIRECTIONS: [Position2dDiff; 6] = [
+        (1, 0), (1, -1), (0, -1),
+        (-1, 0), (-1, 1), (0, 1),
+    ];
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Self> {
+        (q + r + s == 0).then_some(Self { q, r })
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn from_offset(position: &Position2d, layout: HexLayout) -> Self {
+        let (row, col) = (position.0 as i32, position.1 as i32);
+        match layout {
+            HexLayout::OddR => Self::new(col - (row - (row & 1)) / 2, row),
+            HexLayout::EvenR => Self::new(col - (row + (row & 1)) / 2, row),
+            HexLayout::OddQ => Self::new(col, row - (col - (col & 1)) / 2),
+            HexLayout::EvenQ => Self::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+
+    /// Offset position of the coordinate, `None` if it falls at negative rows or columns
+    pub fn to_offset(&self, layout: HexLayout) -> Option<Position2d> {
+        let (row, col) = self.to_signed_offset(layout);
+        Some((row.try_into().ok()?, col.try_into().ok()?))
+    }
+
+    /// Offset row and column of the coordinate, which may be negative
+    pub fn to_signed_offset(&self, layout: HexLayout) -> (i32, i32) {
+        let (q, r) = (self.q, self.r);
+        match layout {
+            HexLayout::OddR => (r, q + (r - (r & 1)) / 2),
+            HexLayout::EvenR => (r, q + (r + (r & 1)) / 2),
+            HexLayout::OddQ => (r + (q - (q & 1)) / 2, q),
+            HexLayout::EvenQ => (r + (q + (q & 1)) / 2, q),
+        }
+    }
+
+    pub fn neighbors(&self) -> [HexCoord; 6] {
+        Self::DIRECTIONS.map(|direction| *self + direction)
+    }
+
+    /// Number of steps between two hexagons
+    pub fn distance(&self, other: &HexCoord) -> usize {
+        let (dq, dr, ds) = (self.q - other.q, self.r - other.r, self.s() - other.s());
+        ((dq.abs() + dr.abs() + ds.abs()) / 2) as usize
+    }
+
+    /// Hexagons crossed by the straight segment between two hexagons, both included
+    pub fn line_to(&self, other: &HexCoord) -> Vec<HexCoord> {
+        let steps = self.distance(other);
+        if steps == 0 {
+            return vec![*self];
+        }
+
+        // nudge the segment so it never runs exactly along an edge between two hexagons
+        let (q0, r0) = (self.q as f64 + 1e-6, self.r as f64 + 2e-6);
+        let (q1, r1) = (other.q as f64 + 1e-6, other.r as f64 + 2e-6);
+        (0..=steps)
+            .map(|step| {
+                let t = step as f64 / steps as f64;
+                Self::round(q0 + (q1 - q0) * t, r0 + (r1 - r0) * t)
+            })
+            .collect()
+    }
+
+    /// Hexagon containing the fractional axial coordinate
+    fn round(q: f64, r: f64) -> HexCoord {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+
+        Self::new(rq as i32, rr as i32)
+    }
+}
+
+impl Add<Position2dDiff> for HexCoord {
+    type Output = HexCoord;
+
+    fn add(self, rhs: Position2dDiff) -> Self::Output {
+        Self::new(self.q + rhs.0, self.r + rhs.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{CellType, Grid, GridBuilder, GridExt};
+
+    const LAYOUTS: [HexLayout; 4] = [
+        HexLayout::OddR,
+        HexLayout::EvenR,
+        HexLayout::OddQ,
+        HexLayout::EvenQ,
+    ];
+
+    fn hex_grid(layout: HexLayout) -> Grid<()> {
+        GridBuilder::new_hex_grid()
+            .with_cell_type(CellType::Hex(layout))
+            .with_rows(5)
+            .with_columns(5)
+            .with_initial_value(())
+            .build()
+    }
+
+    fn neighbors(grid: &Grid<()>, position: Position2d) -> Vec<Position2d> {
+        let mut neighbors: Vec<_> = grid
+            .adjacent_cells(&position)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        for layout in LAYOUTS {
+            for row in 0..6 {
+                for col in 0..6 {
+                    let coord = HexCoord::from_offset(&(row, col), layout);
+                    assert_eq!(coord.to_offset(layout), Some((row, col)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube() {
+        let coord = HexCoord::new(2, -3);
+        assert_eq!(coord.cube(), (2, -3, 1));
+        assert_eq!(HexCoord::from_cube(2, -3, 1), Some(coord));
+        assert_eq!(HexCoord::from_cube(2, -3, 0), None);
+    }
+
+    #[test]
+    fn distance() {
+        let origin = HexCoord::new(0, 0);
+        for neighbor in origin.neighbors() {
+            assert_eq!(origin.distance(&neighbor), 1);
+        }
+        assert_eq!(origin.distance(&HexCoord::new(3, -1)), 3);
+        assert_eq!(origin.distance(&HexCoord::new(-2, -2)), 4);
+    }
+
+    #[test]
+    fn line_to() {
+        let origin = HexCoord::new(0, 0);
+        assert_eq!(origin.line_to(&origin), vec![origin]);
+
+        let line = origin.line_to(&HexCoord::new(3, -1));
+        assert_eq!(line.len(), 4);
+        assert_eq!(line[0], origin);
+        assert_eq!(line[3], HexCoord::new(3, -1));
+        for pair in line.windows(2) {
+            assert_eq!(pair[0].distance(&pair[1]), 1);
+        }
+
+        assert_eq!(
+            origin.line_to(&HexCoord::new(0, 3)),
+            vec![
+                origin,
+                HexCoord::new(0, 1),
+                HexCoord::new(0, 2),
+                HexCoord::new(0, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn odd_r_neighbors() {
+        let grid = hex_grid(HexLayout::OddR);
+        assert_eq!(
+            neighbors(&grid, (2, 2)),
+            vec![(1, 1), (1, 2), (2, 1), (2, 3), (3, 1), (3, 2)]
+        );
+        assert_eq!(
+            neighbors(&grid, (1, 2)),
+            vec![(0, 2), (0, 3), (1, 1), (1, 3), (2, 2), (2, 3)]
+        );
+        assert_eq!(neighbors(&grid, (0, 0)), vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn even_r_neighbors() {
+        let grid = hex_grid(HexLayout::EvenR);
+        assert_eq!(
+            neighbors(&grid, (2, 2)),
+            vec![(1, 2), (1, 3), (2, 1), (2, 3), (3, 2), (3, 3)]
+        );
+        assert_eq!(
+            neighbors(&grid, (1, 2)),
+            vec![(0, 1), (0, 2), (1, 1), (1, 3), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn odd_q_neighbors() {
+        let grid = hex_grid(HexLayout::OddQ);
+        assert_eq!(
+            neighbors(&grid, (2, 2)),
+            vec![(1, 1), (1, 2), (1, 3), (2, 1), (2, 3), (3, 2)]
+        );
+        assert_eq!(
+            neighbors(&grid, (2, 1)),
+            vec![(1, 1), (2, 0), (2, 2), (3, 0), (3, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn even_q_neighbors() {
+        let grid = hex_grid(HexLayout::EvenQ);
+        assert_eq!(
+            neighbors(&grid, (2, 2)),
+            vec![(1, 2), (2, 1), (2, 3), (3, 1), (3, 2), (3, 3)]
+        );
+        assert_eq!(
+            neighbors(&grid, (2, 1)),
+            vec![(1, 0), (1, 1), (1, 2), (2, 0), (2, 2), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn neighbors_are_symmetric() {
+        for layout in LAYOUTS {
+            let grid = hex_grid(layout);
+            for row in 0..5 {
+                for col in 0..5 {
+                    for neighbor in neighbors(&grid, (row, col)) {
+                        assert!(neighbors(&grid, neighbor).contains(&(row, col)));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use super::grid::{Grid, GridExt, Position2d, Position2dDiff};
+
+/// Straight run of cells, ordered from start to end along `direction`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Line<P = Position2d, D = Position2dDiff> {
+    pub direction: D,
+    pub cells: Vec<P>,
+}
+
+impl<P: Copy, D> Line<P, D> {
+    pub fn start(&self) -> P {
+        self.cells[0]
+    }
+
+    pub fn end(&self) -> P {
+        self.cells[self.cells.len() - 1]
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+pub trait LineDetection<T> {
+    type Position;
+    type Direction;
+
+    /// Every maximal run of at least `min_length` equal cells, along every line direction
+    fn lines(&self, min_length: usize) -> Vec<Line<Self::Position, Self::Direction>>
+    where
+        T: PartialEq;
+
+    /// Every maximal run of at least `min_length` cells accepted by `predicate`
+    fn lines_matching<F>(
+        &self,
+        min_length: usize,
+        predicate: F,
+    ) -> Vec<Line<Self::Position, Self::Direction>>
+    where
+        F: Fn(&T) -> bool;
+
+    /// Like [`LineDetection::lines_matching`] but only along `directions`,
+    /// regardless of the grid adjacency
+    fn lines_along<F>(
+        &self,
+        directions: &[Self::Direction],
+        min_length: usize,
+        predicate: F,
+    ) -> Vec<Line<Self::Position, Self::Direction>>
+    where
+        F: Fn(&T) -> bool;
+}
+
+/// Directions that can be followed backwards
+pub(super) trait Reverse {
+    fn reverse(self) -> Self;
+}
+
+impl Reverse for Position2dDiff {
+    fn reverse(self) -> Self {
+        (-self.0, -self.1)
+    }
+}
+
+/// Every maximal run of at least `min_length` cells along `directions`, for
+/// any board listing its `positions` and moving between them with `step`.
+///
+/// Cells must be accepted by `accept` and match the first one of the run by `same`.
+pub(super) fn runs<'b, T: 'b, P, D>(
+    positions: impl Iterator<Item = P> + Clone,
+    directions: &[D],
+    min_length: usize,
+    get: impl Fn(&P) -> Option<&'b T>,
+    step: impl Fn(&P, D) -> Option<P>,
+    accept: impl Fn(&T) -> bool,
+    same: impl Fn(&T, &T) -> bool,
+) -> Vec<Line<P, D>>
+where
+    P: Copy + Ord,
+    D: Copy + Reverse,
+{
+    let mut lines = vec![];
+    for &direction in directions {
+        for start in positions.clone() {
+            let cell = get(&start).expect("inside the board");
+            if !accept(cell) {
+                continue;
+            }
+
+            let mut cells = vec![start];
+            let mut closed = false;
+            while let Some(next) = step(cells.last().unwrap(), direction) {
+                let next_cell = get(&next).expect("inside the board");
+                if !accept(next_cell) || !same(cell, next_cell) {
+                    break;
+                }
+                if next == start {
+                    closed = true;
+                    break;
+                }
+                cells.push(next);
+            }
+
+            // Only report a run from its first cell, or from the smallest
+            // one when it loops around a wrapped board
+            let previous = step(&start, direction.reverse()).and_then(|p| get(&p));
+            let first = if closed {
+                cells.iter().all(|cell| start <= *cell)
+            } else {
+                !previous.is_some_and(|previous| accept(previous) && same(previous, cell))
+            };
+
+            if first && cells.len() >= min_length {
+                lines.push(Line { direction, cells });
+            }
+        }
+    }
+
+    lines
+}
+
+impl<T> Grid<T> {
+    fn runs<A, S>(
+        &self,
+        directions: &[Position2dDiff],
+        min_length: usize,
+        accept: A,
+        same: S,
+    ) -> Vec<Line>
+    where
+        A: Fn(&T) -> bool,
+        S: Fn(&T, &T) -> bool,
+    {
+        let columns = self.column_len();
+        runs(
+            (0..self.row_len()).flat_map(move |row| (0..columns).map(move |column| (row, column))),
+            directions,
+            min_length,
+            |position| self.get(position),
+            |position, direction| self.step(position, direction),
+            accept,
+            same,
+        )
+    }
+}
+
+impl<T> LineDetection<T> for Grid<T> {
+    type Position = Position2d;
+    type Direction = Position2dDiff;
+
+    fn lines(&self, min_length: usize) -> Vec<Line>
+    where
+        T: PartialEq,
+    {
+        self.runs(&self.line_directions(), min_length, |_| true, |a, b| a == b)
+    }
+
+    fn lines_matching<F>(&self, min_length: usize, predicate: F) -> Vec<Line>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.runs(&self.line_directions(), min_length, predicate, |_, _| true)
+    }
+
+    fn lines_along<F>(
+        &self,
+        directions: &[Position2dDiff],
+        min_length: usize,
+        predicate: F,
+    ) -> Vec<Line>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.runs(directions, min_length, predicate, |_, _| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{CellAdjacency, GridBuilder, GridExtMut, Topology};
+
+    fn square(rows: &[&str], adjacency: CellAdjacency) -> Grid<char> {
+        let mut grid = GridBuilder::new_square_grid()
+            .with_cell_adjacency(adjacency)
+            .with_rows(rows.len())
+            .with_columns(rows[0].len())
+            .with_initial_value('.')
+            .build();
+
+        for (row, cells) in rows.iter().enumerate() {
+            for (column, cell) in cells.chars().enumerate() {
+                *grid.get_mut(&(row, column)).unwrap() = cell;
+            }
+        }
+
+        grid
+    }
+
+    #[test]
+    fn row_and_column() {
+        let grid = square(&["xxx.", "x...", "x..."], CellAdjacency::Side);
+        let lines = grid.lines_matching(2, |cell| *cell == 'x');
+
+        assert_eq!(
+            lines,
+            vec![
+                Line {
+                    direction: (0, 1),
+                    cells: vec![(0, 0), (0, 1), (0, 2)]
+                },
+                Line {
+                    direction: (1, 0),
+                    cells: vec![(0, 0), (1, 0), (2, 0)]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn maximal_runs_only() {
+        let grid = square(&["xxxx"], CellAdjacency::Side);
+        let lines = grid.lines_matching(3, |cell| *cell == 'x');
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].start(), (0, 0));
+        assert_eq!(lines[0].end(), (0, 3));
+    }
+
+    #[test]
+    fn diagonals_need_vertex_adjacency() {
+        let rows = ["x..", ".x.", "..x"];
+        let side = square(&rows, CellAdjacency::Side);
+        assert!(side.lines_matching(2, |cell| *cell == 'x').is_empty());
+
+        let vertex = square(&rows, CellAdjacency::SideAndVertex);
+        let lines = vertex.lines_matching(2, |cell| *cell == 'x');
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].direction, (1, 1));
+        assert_eq!(lines[0].len(), 3);
+    }
+
+    #[test]
+    fn explicit_directions() {
+        let grid = square(&["x..", ".x.", "..x"], CellAdjacency::Side);
+        let lines = grid.lines_along(&[(1, 1)], 3, |cell| *cell == 'x');
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].start(), (0, 0));
+    }
+
+    #[test]
+    fn anti_diagonal() {
+        let grid = square(&["..o", ".o.", "o.."], CellAdjacency::SideAndVertex);
+        let lines = grid.lines_matching(3, |cell| *cell == 'o');
+
+        assert_eq!(
+            lines,
+            vec![Line {
+                direction: (1, -1),
+                cells: vec![(0, 2), (1, 1), (2, 0)]
+            }]
+        );
+    }
+
+    #[test]
+    fn runs_of_equal_cells() {
+        let grid = square(&["xxoo"], CellAdjacency::Side);
+        let lines = grid.lines(2);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].cells, vec![(0, 0), (0, 1)]);
+        assert_eq!(lines[1].cells, vec![(0, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn hex_axes() {
+        let mut grid = GridBuilder::new_hex_grid()
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(false)
+            .build();
+
+        // odd-r layout: (0, 1) -> (1, 1) -> (2, 2) -> (3, 2) is a straight axial line
+        for position in [(0, 1), (1, 1), (2, 2), (3, 2)] {
+            *grid.get_mut(&position).unwrap() = true;
+        }
+
+        let lines = grid.lines_matching(3, |cell| *cell);
+        assert_eq!(
+            lines,
+            vec![Line {
+                direction: (0, 1),
+                cells: vec![(0, 1), (1, 1), (2, 2), (3, 2)]
+            }]
+        );
+    }
+
+    #[test]
+    fn wrapped_runs() {
+        let mut grid: Grid<char> = GridBuilder::new_square_grid()
+            .with_rows(2)
+            .with_columns(4)
+            .with_initial_value('x')
+            .with_topology(Topology::WrapColumns)
+            .build();
+        *grid.get_mut(&(1, 1)).unwrap() = '.';
+
+        let rows: Vec<_> = grid
+            .lines_along(&[(0, 1)], 3, |cell| *cell == 'x')
+            .into_iter()
+            .map(|line| line.cells)
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                vec![(0, 0), (0, 1), (0, 2), (0, 3)],
+                vec![(1, 2), (1, 3), (1, 0)]
+            ]
+        );
+    }
+}
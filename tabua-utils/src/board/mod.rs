@@ -1 +1,14 @@
+pub mod bitboard;
+pub mod graph;
 pub mod grid;
+pub mod grid3d;
+pub mod hex;
+pub mod line;
+pub mod neighbors;
+pub mod ray;
+pub mod region;
+pub mod sparse;
+pub mod symmetry;
+pub mod triangle;
+pub mod view;
+pub mod zobrist;
@@ -0,0 +1,27 @@
+use std::hash::Hash;
+
+use super::grid::{Grid, GridExt, Position2d};
+
+/// Board whose cells are walked from neighbor to neighbor, whatever its shape.
+///
+/// The [`pathfinder`](crate::pathfinder) searches work on any implementation.
+pub trait Neighbors {
+    type Position: Copy + Eq + Ord + Hash;
+    type Cell;
+
+    fn cell(&self, position: &Self::Position) -> Option<&Self::Cell>;
+    fn neighbors(&self, position: &Self::Position) -> Vec<(Self::Position, &Self::Cell)>;
+}
+
+impl<T> Neighbors for Grid<T> {
+    type Position = Position2d;
+    type Cell = T;
+
+    fn cell(&self, position: &Position2d) -> Option<&T> {
+        self.get(position)
+    }
+
+    fn neighbors(&self, position: &Position2d) -> Vec<(Position2d, &T)> {
+        self.adjacent_cells(position)
+    }
+}
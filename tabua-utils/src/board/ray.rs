@@ -0,0 +1,244 @@
+use super::grid::{CellType, Grid, GridExt, Position2d, Position2dDiff};
+
+/// Cells met walking from a position in a fixed direction, until the board edge
+/// or, on wrapped grids, until coming back to the origin
+pub struct Ray<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position2d,
+    position: Position2d,
+    direction: Position2dDiff,
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = (Position2d, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.position = self.grid.step(&self.position, self.direction)?;
+        if self.position == self.origin {
+            return None;
+        }
+        Some((self.position, self.grid.get(&self.position)?))
+    }
+}
+
+pub trait RayCasting<T> {
+    /// Cells along `direction` starting next to `origin`, see [`Grid::line_directions`]
+    fn ray(&self, origin: &Position2d, direction: Position2dDiff) -> Ray<'_, T>;
+
+    /// Cells along `direction` up to and including the first one accepted by `blocking`,
+    /// the way a rook slides until it hits a piece
+    fn ray_until<F>(
+        &self,
+        origin: &Position2d,
+        direction: Position2dDiff,
+        blocking: F,
+    ) -> Vec<Position2d>
+    where
+        F: Fn(&T) -> bool;
+
+    /// Cells crossed by the straight segment between two cells, both included.
+    ///
+    /// The segment never crosses the edges of wrapped grids.
+    fn line_between(&self, from: &Position2d, to: &Position2d) -> Vec<Position2d>;
+
+    /// Whether no cell strictly between `from` and `to` is accepted by `blocking`
+    fn line_of_sight<F>(&self, from: &Position2d, to: &Position2d, blocking: F) -> bool
+    where
+        F: Fn(&T) -> bool;
+}
+
+/// Bresenham line between two square cells
+fn bresenham(from: &Position2d, to: &Position2d) -> Vec<Position2d> {
+    let (mut row, mut col) = (from.0 as i64, from.1 as i64);
+    let (to_row, to_col) = (to.0 as i64, to.1 as i64);
+    let (d_row, d_col) = ((to_row - row).abs(), -(to_col - col).abs());
+    let (step_row, step_col) = ((to_row - row).signum(), (to_col - col).signum());
+
+    let mut cells = vec![];
+    let mut error = d_row + d_col;
+    loop {
+        cells.push((row as usize, col as usize));
+        if (row, col) == (to_row, to_col) {
+            return cells;
+        }
+
+        let double = 2 * error;
+        if double >= d_col {
+            error += d_col;
+            row += step_row;
+        }
+        if double <= d_row {
+            error += d_row;
+            col += step_col;
+        }
+    }
+}
+
+impl<T> RayCasting<T> for Grid<T> {
+    fn ray(&self, origin: &Position2d, direction: Position2dDiff) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            origin: *origin,
+            position: *origin,
+            direction,
+        }
+    }
+
+    fn ray_until<F>(
+        &self,
+        origin: &Position2d,
+        direction: Position2dDiff,
+        blocking: F,
+    ) -> Vec<Position2d>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut cells = vec![];
+        for (position, cell) in self.ray(origin, direction) {
+            cells.push(position);
+            if blocking(cell) {
+                break;
+            }
+        }
+        cells
+    }
+
+    fn line_between(&self, from: &Position2d, to: &Position2d) -> Vec<Position2d> {
+        match self.cell_type() {
+            // triangles have no straight lines across rows, follow the offset positions
+            CellType::Square | CellType::Triangle => bresenham(from, to),
+            CellType::Hex(_) => match (self.hex_coord(from), self.hex_coord(to)) {
+                (Some(from), Some(to)) => from
+                    .line_to(&to)
+                    .iter()
+                    .filter_map(|coord| self.hex_position(coord))
+                    .collect(),
+                _ => vec![],
+            },
+        }
+    }
+
+    fn line_of_sight<F>(&self, from: &Position2d, to: &Position2d, blocking: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.line_between(from, to)
+            .iter()
+            .filter(|position| *position != from && *position != to)
+            .all(|position| !self.get(position).is_some_and(&blocking))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{CellAdjacency, GridBuilder, GridExtMut, Topology};
+
+    fn board(rows: &[&str]) -> Grid<char> {
+        Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn ray_to_edge() {
+        let grid = board(&["....", "....", "...."]);
+
+        let cells: Vec<_> = grid.ray(&(1, 0), (0, 1)).map(|(p, _)| p).collect();
+        assert_eq!(cells, vec![(1, 1), (1, 2), (1, 3)]);
+
+        let cells: Vec<_> = grid.ray(&(0, 0), (1, 1)).map(|(p, _)| p).collect();
+        assert_eq!(cells, vec![(1, 1), (2, 2)]);
+
+        assert_eq!(grid.ray(&(0, 0), (-1, 0)).count(), 0);
+    }
+
+    #[test]
+    fn ray_until_blocking() {
+        let grid = board(&["R..x.", "....."]);
+        let cells = grid.ray_until(&(0, 0), (0, 1), |cell| *cell != '.');
+
+        assert_eq!(cells, vec![(0, 1), (0, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn reversi_flips() {
+        let grid = board(&["bwwb.", "....."]);
+        let flips: Vec<_> = grid
+            .ray(&(0, 0), (0, 1))
+            .take_while(|(_, cell)| **cell == 'w')
+            .map(|(p, _)| p)
+            .collect();
+
+        assert_eq!(flips, vec![(0, 1), (0, 2)]);
+        assert_eq!(grid.get(&(0, 3)), Some(&'b'));
+    }
+
+    #[test]
+    fn hex_ray() {
+        let grid: Grid<()> = GridBuilder::new_hex_grid()
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(())
+            .build();
+
+        let cells: Vec<_> = grid.ray(&(0, 1), (0, 1)).map(|(p, _)| p).collect();
+        assert_eq!(cells, vec![(1, 1), (2, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn square_line_between() {
+        let grid = board(&["....", "....", "...."]);
+
+        assert_eq!(
+            grid.line_between(&(0, 0), &(2, 3)),
+            vec![(0, 0), (1, 1), (1, 2), (2, 3)]
+        );
+        assert_eq!(
+            grid.line_between(&(2, 1), &(0, 1)),
+            vec![(2, 1), (1, 1), (0, 1)]
+        );
+        assert_eq!(grid.line_between(&(1, 1), &(1, 1)), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn line_of_sight() {
+        let grid = board(&["a...", ".#..", "...b"]);
+        let wall = |cell: &char| *cell == '#';
+
+        assert!(!grid.line_of_sight(&(0, 0), &(2, 3), wall));
+        assert!(grid.line_of_sight(&(0, 0), &(0, 3), wall));
+        assert!(grid.line_of_sight(&(0, 0), &(1, 1), wall));
+    }
+
+    #[test]
+    fn hex_line_of_sight() {
+        let mut grid: Grid<bool> = GridBuilder::new_hex_grid()
+            .with_cell_adjacency(CellAdjacency::Side)
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(false)
+            .build();
+
+        assert_eq!(
+            grid.line_between(&(0, 1), &(3, 2)),
+            vec![(0, 1), (1, 1), (2, 2), (3, 2)]
+        );
+
+        *grid.get_mut(&(2, 2)).unwrap() = true;
+        assert!(!grid.line_of_sight(&(0, 1), &(3, 2), |wall| *wall));
+        assert!(grid.line_of_sight(&(0, 1), &(0, 3), |wall| *wall));
+    }
+
+    #[test]
+    fn wrapped_ray_stops_at_origin() {
+        let grid: Grid<()> = GridBuilder::new_square_grid()
+            .with_rows(2)
+            .with_columns(3)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+
+        let cells: Vec<_> = grid.ray(&(0, 1), (0, 1)).map(|(p, _)| p).collect();
+        assert_eq!(cells, vec![(0, 2), (0, 0)]);
+        assert_eq!(grid.ray(&(0, 0), (1, 1)).count(), 5);
+    }
+}
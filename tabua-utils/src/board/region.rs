@@ -0,0 +1,238 @@
+use std::collections::VecDeque;
+
+use super::grid::{Grid, GridBuilder, GridExt, GridExtMut, Position2d};
+
+/// Connected set of cells, following the grid adjacency
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Region {
+    pub id: usize,
+    pub cells: Vec<Position2d>,
+    /// Cells outside the region adjacent to at least one of its cells, sorted
+    pub frontier: Vec<Position2d>,
+}
+
+impl Region {
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Result of labeling every cell of a grid with the region it belongs to
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Components {
+    /// Region id of each cell, `None` for cells left out of every region
+    pub labels: Grid<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+pub trait RegionAnalysis<T> {
+    /// Region of the cells accepted by `predicate` connected to `start`,
+    /// `None` if `start` is outside the board or not accepted
+    fn flood_fill<F>(&self, start: &Position2d, predicate: F) -> Option<Region>
+    where
+        F: Fn(&T) -> bool;
+
+    /// Split the whole board in regions of adjacent equal cells
+    fn connected_components(&self) -> Components
+    where
+        T: PartialEq;
+
+    /// Split the cells accepted by `predicate` in regions of adjacent cells
+    fn connected_components_matching<F>(&self, predicate: F) -> Components
+    where
+        F: Fn(&T) -> bool;
+
+    /// Frontier cells of `region` accepted by `predicate`, e.g. the liberties of a Go group
+    fn liberties<F>(&self, region: &Region, predicate: F) -> Vec<Position2d>
+    where
+        F: Fn(&T) -> bool;
+}
+
+impl<T> Grid<T> {
+    fn labels(&self) -> Grid<Option<usize>> {
+        GridBuilder::new()
+            .with_cell_type(self.cell_type())
+            .with_cell_adjacency(self.cell_adjacency())
+            .with_topology(self.topology())
+            .with_rows(self.row_len())
+            .with_columns(self.column_len())
+            .with_initial_value(None)
+            .build()
+    }
+
+    fn fill<F>(
+        &self,
+        start: &Position2d,
+        id: usize,
+        belongs: F,
+        labels: &mut Grid<Option<usize>>,
+    ) -> Region
+    where
+        F: Fn(&T) -> bool,
+    {
+        *labels.get_mut(start).expect("start inside the board") = Some(id);
+
+        let mut cells = vec![];
+        let mut frontier = vec![];
+        let mut queue = VecDeque::from([*start]);
+        while let Some(current) = queue.pop_front() {
+            cells.push(current);
+
+            for (next, cell) in self.adjacent_cells(&current) {
+                if labels.get(&next) == Some(&Some(id)) {
+                    continue;
+                }
+
+                if labels.get(&next) == Some(&None) && belongs(cell) {
+                    *labels.get_mut(&next).unwrap() = Some(id);
+                    queue.push_back(next);
+                } else {
+                    frontier.push(next);
+                }
+            }
+        }
+
+        frontier.sort();
+        frontier.dedup();
+
+        Region {
+            id,
+            cells,
+            frontier,
+        }
+    }
+
+    fn components<F, S>(&self, accept: F, same: S) -> Components
+    where
+        F: Fn(&T) -> bool,
+        S: Fn(&T, &T) -> bool,
+    {
+        let mut labels = self.labels();
+        let mut regions = vec![];
+
+        for row in 0..self.row_len() {
+            for column in 0..self.column_len() {
+                let start = (row, column);
+                let cell = self.get(&start).expect("inside the board");
+                if labels.get(&start) != Some(&None) || !accept(cell) {
+                    continue;
+                }
+
+                let belongs = |other: &T| accept(other) && same(cell, other);
+                regions.push(self.fill(&start, regions.len(), belongs, &mut labels));
+            }
+        }
+
+        Components { labels, regions }
+    }
+}
+
+impl<T> RegionAnalysis<T> for Grid<T> {
+    fn flood_fill<F>(&self, start: &Position2d, predicate: F) -> Option<Region>
+    where
+        F: Fn(&T) -> bool,
+    {
+        if !predicate(self.get(start)?) {
+            return None;
+        }
+
+        Some(self.fill(start, 0, predicate, &mut self.labels()))
+    }
+
+    fn connected_components(&self) -> Components
+    where
+        T: PartialEq,
+    {
+        self.components(|_| true, |a, b| a == b)
+    }
+
+    fn connected_components_matching<F>(&self, predicate: F) -> Components
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.components(predicate, |_, _| true)
+    }
+
+    fn liberties<F>(&self, region: &Region, predicate: F) -> Vec<Position2d>
+    where
+        F: Fn(&T) -> bool,
+    {
+        region
+            .frontier
+            .iter()
+            .filter(|p| self.get(p).is_some_and(&predicate))
+            .copied()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(rows: &[&str]) -> Grid<char> {
+        Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn flood_fill() {
+        let grid = board(&["..#.", ".##.", "...."]);
+        let region = grid.flood_fill(&(0, 2), |cell| *cell == '#').unwrap();
+
+        assert_eq!(region.size(), 3);
+        assert_eq!(region.cells, vec![(0, 2), (1, 2), (1, 1)]);
+        assert_eq!(
+            region.frontier,
+            vec![(0, 1), (0, 3), (1, 0), (1, 3), (2, 1), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn flood_fill_rejected_start() {
+        let grid = board(&["..#"]);
+
+        assert_eq!(grid.flood_fill(&(0, 0), |cell| *cell == '#'), None);
+        assert_eq!(grid.flood_fill(&(3, 0), |cell| *cell == '#'), None);
+    }
+
+    #[test]
+    fn connected_components() {
+        let grid = board(&["xxo", "oxo", "oox"]);
+        let components = grid.connected_components();
+
+        let sizes: Vec<_> = components.regions.iter().map(Region::size).collect();
+        assert_eq!(sizes, vec![3, 2, 3, 1]);
+        assert_eq!(components.labels.get(&(1, 1)), Some(&Some(0)));
+        assert_eq!(components.labels.get(&(2, 1)), Some(&Some(2)));
+        assert_eq!(components.labels.get(&(2, 2)), Some(&Some(3)));
+    }
+
+    #[test]
+    fn components_matching() {
+        let grid = board(&["x.x", "x.x", "..."]);
+        let components = grid.connected_components_matching(|cell| *cell == 'x');
+
+        assert_eq!(components.regions.len(), 2);
+        assert_eq!(components.labels.get(&(0, 1)), Some(&None));
+    }
+
+    #[test]
+    fn liberties() {
+        let grid = board(&[".xo", "xoo", ".x."]);
+        let group = grid.flood_fill(&(1, 1), |cell| *cell == 'o').unwrap();
+
+        assert_eq!(grid.liberties(&group, |cell| *cell == '.'), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn hex_region() {
+        let grid = Grid::new_hex_grid(vec![vec![1, 0, 0], vec![0, 1, 0], vec![1, 0, 0]]);
+
+        // in odd-r layout (1, 1) touches (0, 1) and (0, 2), not (0, 0)
+        let region = grid.flood_fill(&(1, 1), |cell| *cell == 1).unwrap();
+        assert_eq!(region.size(), 1);
+
+        let region = grid.flood_fill(&(1, 0), |cell| *cell == 0).unwrap();
+        assert_eq!(region.size(), 6);
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::grid::{CellAdjacency, CellType, Position2dDiff};
+use super::hex::{HexCoord, HexLayout};
+use super::neighbors::Neighbors;
+use super::triangle::TriCoord;
+
+/// Position on a board without edges, it can go below zero
+pub type SignedPosition2d = (i32, i32);
+
+/// Unbounded board only storing the cells that were set.
+///
+/// Square boards use `(row, column)` positions while hex boards use axial
+/// `(q, r)` coordinates, as in [`HexCoord`], so the layout of a `CellType::Hex`
+/// plays no role. Triangle boards use `(row, column)` positions like [`TriCoord`]
+/// offsets. Searches walk through the set cells only.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct SparseBoard<T> {
+    #[serde(with = "cells")]
+    cells: BTreeMap<SignedPosition2d, T>,
+    cell_type: CellType,
+    cell_adjacency: CellAdjacency,
+}
+
+/// Cells are saved as position and cell pairs, positions can't be map keys in most formats
+mod cells {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::SignedPosition2d;
+
+    pub fn serialize<S, T>(
+        cells: &BTreeMap<SignedPosition2d, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_seq(cells)
+    }
+
+    pub fn deserialize<'de, D, T>(
+        deserializer: D,
+    ) -> Result<BTreeMap<SignedPosition2d, T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let cells = Vec::<(SignedPosition2d, T)>::deserialize(deserializer)?;
+        Ok(cells.into_iter().collect())
+    }
+}
+
+impl<T> SparseBoard<T> {
+    pub fn new_square() -> Self {
+        Self {
+            cells: BTreeMap::new(),
+            cell_type: CellType::Square,
+            cell_adjacency: CellAdjacency::Side,
+        }
+    }
+
+    pub fn new_hex() -> Self {
+        Self {
+            cells: BTreeMap::new(),
+            cell_type: CellType::Hex(HexLayout::default()),
+            cell_adjacency: CellAdjacency::Side,
+        }
+    }
+
+    pub fn new_triangle() -> Self {
+        Self {
+            cells: BTreeMap::new(),
+            cell_type: CellType::Triangle,
+            cell_adjacency: CellAdjacency::Side,
+        }
+    }
+
+    pub fn with_cell_adjacency(mut self, cell_adjacency: CellAdjacency) -> Self {
+        self.cell_adjacency = cell_adjacency;
+        self
+    }
+
+    pub fn cell_type(&self) -> CellType {
+        self.cell_type
+    }
+
+    pub fn cell_adjacency(&self) -> CellAdjacency {
+        self.cell_adjacency
+    }
+
+    pub fn get(&self, position: &SignedPosition2d) -> Option<&T> {
+        self.cells.get(position)
+    }
+
+    pub fn get_mut(&mut self, position: &SignedPosition2d) -> Option<&mut T> {
+        self.cells.get_mut(position)
+    }
+
+    /// Set a cell, returning its previous value
+    pub fn insert(&mut self, position: SignedPosition2d, cell: T) -> Option<T> {
+        self.cells.insert(position, cell)
+    }
+
+    pub fn remove(&mut self, position: &SignedPosition2d) -> Option<T> {
+        self.cells.remove(position)
+    }
+
+    pub fn contains(&self, position: &SignedPosition2d) -> bool {
+        self.cells.contains_key(position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Set cells, sorted by position
+    pub fn iter(&self) -> impl Iterator<Item = (&SignedPosition2d, &T)> {
+        self.cells.iter()
+    }
+
+    /// Offsets of the positions adjacent to `position`, triangles depend on its parity
+    pub fn adjacencies(&self, position: &SignedPosition2d) -> Vec<Position2dDiff> {
+        match self.cell_type {
+            CellType::Square => self.cell_adjacency.square_offsets(),
+            CellType::Hex(_) => HexCoord::DIRECTIONS.to_vec(),
+            CellType::Triangle => {
+                TriCoord::from_signed_offset(*position).adjacencies(self.cell_adjacency)
+            }
+        }
+    }
+
+    /// Positions adjacent to `position`, set or not
+    pub fn adjacent_positions(&self, position: &SignedPosition2d) -> Vec<SignedPosition2d> {
+        self.adjacencies(position)
+            .into_iter()
+            .map(|diff| (position.0 + diff.0, position.1 + diff.1))
+            .collect()
+    }
+
+    /// Set cells adjacent to `position`
+    pub fn adjacent_cells(&self, position: &SignedPosition2d) -> Vec<(SignedPosition2d, &T)> {
+        self.adjacent_positions(position)
+            .into_iter()
+            .filter_map(|p| Some((p, self.get(&p)?)))
+            .collect()
+    }
+
+    /// Unset positions adjacent to some set cell, sorted, e.g. where a new tile can be laid
+    pub fn frontier(&self) -> Vec<SignedPosition2d> {
+        let mut frontier: Vec<_> = self
+            .cells
+            .keys()
+            .flat_map(|position| self.adjacent_positions(position))
+            .filter(|position| !self.contains(position))
+            .collect();
+        frontier.sort();
+        frontier.dedup();
+        frontier
+    }
+
+    /// Number of steps between two positions
+    pub fn distance(&self, from: &SignedPosition2d, to: &SignedPosition2d) -> u32 {
+        let (d0, d1) = (from.0.abs_diff(to.0), from.1.abs_diff(to.1));
+        match (self.cell_type, self.cell_adjacency) {
+            (CellType::Square, CellAdjacency::Side) => d0 + d1,
+            (CellType::Square, CellAdjacency::SideAndVertex) => d0.max(d1),
+            (CellType::Hex(_), _) => {
+                HexCoord::new(from.0, from.1).distance(&HexCoord::new(to.0, to.1)) as u32
+            }
+            (CellType::Triangle, adjacency) => {
+                let (from, to) = (
+                    TriCoord::from_signed_offset(*from),
+                    TriCoord::from_signed_offset(*to),
+                );
+                match adjacency {
+                    CellAdjacency::Side => from.distance(&to) as u32,
+                    CellAdjacency::SideAndVertex => from.vertex_distance(&to) as u32,
+                }
+            }
+        }
+    }
+
+    /// Smallest and largest coordinates of the set cells, `None` for an empty board
+    pub fn bounding_box(&self) -> Option<(SignedPosition2d, SignedPosition2d)> {
+        let mut positions = self.cells.keys();
+        let first = *positions.next()?;
+        Some(positions.fold((first, first), |(min, max), p| {
+            (
+                (min.0.min(p.0), min.1.min(p.1)),
+                (max.0.max(p.0), max.1.max(p.1)),
+            )
+        }))
+    }
+
+    /// Every position of the bounding box, first coordinate outermost, with its cell if set
+    pub fn iter_bounding_box(&self) -> impl Iterator<Item = (SignedPosition2d, Option<&T>)> + '_ {
+        self.bounding_box()
+            .into_iter()
+            .flat_map(|(min, max)| {
+                (min.0..=max.0).flat_map(move |a| (min.1..=max.1).map(move |b| (a, b)))
+            })
+            .map(|position| (position, self.get(&position)))
+    }
+}
+
+impl<T> FromIterator<(SignedPosition2d, T)> for SparseBoard<T> {
+    /// Square board holding the given cells
+    fn from_iter<I: IntoIterator<Item = (SignedPosition2d, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+            ..Self::new_square()
+        }
+    }
+}
+
+impl<T> Neighbors for SparseBoard<T> {
+    type Position = SignedPosition2d;
+    type Cell = T;
+
+    fn cell(&self, position: &SignedPosition2d) -> Option<&T> {
+        self.get(position)
+    }
+
+    fn neighbors(&self, position: &SignedPosition2d) -> Vec<(SignedPosition2d, &T)> {
+        self.adjacent_cells(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathfinder::bfs::BreadthFirstSearch;
+    use crate::pathfinder::dijkstra::Dijkstra;
+
+    #[test]
+    fn cells() {
+        let mut board = SparseBoard::new_square();
+        assert!(board.is_empty());
+
+        assert_eq!(board.insert((-3, 7), 'a'), None);
+        assert_eq!(board.insert((-3, 7), 'b'), Some('a'));
+        *board.get_mut(&(-3, 7)).unwrap() = 'c';
+
+        assert_eq!(board.get(&(-3, 7)), Some(&'c'));
+        assert_eq!(board.len(), 1);
+        assert_eq!(board.remove(&(-3, 7)), Some('c'));
+        assert!(!board.contains(&(-3, 7)));
+    }
+
+    #[test]
+    fn frontier() {
+        let board: SparseBoard<()> = [((0, 0), ()), ((0, 1), ())].into_iter().collect();
+
+        assert_eq!(
+            board.frontier(),
+            vec![(-1, 0), (-1, 1), (0, -1), (0, 2), (1, 0), (1, 1)]
+        );
+        assert_eq!(board.adjacent_cells(&(0, 0)), vec![((0, 1), &())]);
+    }
+
+    #[test]
+    fn hex_neighbors() {
+        let mut board = SparseBoard::new_hex();
+        board.insert((0, 0), ());
+
+        assert_eq!(board.frontier().len(), 6);
+        assert_eq!(board.distance(&(0, 0), &(2, -1)), 2);
+        assert_eq!(board.distance(&(0, 0), &(2, 1)), 3);
+    }
+
+    #[test]
+    fn triangle_neighbors() {
+        let mut board = SparseBoard::new_triangle();
+        board.insert((0, 0), ());
+        board.insert((-1, 0), ());
+
+        assert_eq!(
+            board.frontier(),
+            vec![(-2, 0), (-1, -1), (-1, 1), (0, -1), (0, 1), (1, 0)]
+        );
+        assert_eq!(board.distance(&(0, 0), &(-1, 0)), 3);
+        assert_eq!(
+            board
+                .with_cell_adjacency(CellAdjacency::SideAndVertex)
+                .distance(&(0, 0), &(-1, 0)),
+            1
+        );
+    }
+
+    #[test]
+    fn bounding_box() {
+        let board: SparseBoard<char> = [((-1, 2), 'a'), ((1, 0), 'b')].into_iter().collect();
+
+        assert_eq!(board.bounding_box(), Some(((-1, 0), (1, 2))));
+        let cells: Vec<_> = board
+            .iter_bounding_box()
+            .filter_map(|(position, cell)| Some((position, *cell?)))
+            .collect();
+        assert_eq!(cells, vec![((-1, 2), 'a'), ((1, 0), 'b')]);
+        assert_eq!(board.iter_bounding_box().count(), 9);
+
+        assert_eq!(SparseBoard::<char>::new_square().bounding_box(), None);
+    }
+
+    #[test]
+    fn pathfinding() {
+        // a horseshoe of tiles, the path goes around the gap
+        let board: SparseBoard<u32> = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2)]
+            .into_iter()
+            .map(|position| (position, 1))
+            .collect();
+
+        let path = board.bfs(&(0, 0), &(0, 2)).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_eq!(board.bfs(&(0, 0), &(0, 1)), None);
+
+        let (_, cost) = board
+            .dijkstra(&(0, 0), &(0, 2), |_, _, cell| Some(*cell))
+            .unwrap();
+        assert_eq!(cost, 6);
+    }
+
+    #[test]
+    fn serde() {
+        let board: SparseBoard<char> = [((-1, 2), 'a'), ((1, 0), 'b')].into_iter().collect();
+        let json = serde_json::to_string(&board).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<SparseBoard<char>>(&json).unwrap(),
+            board
+        );
+    }
+}
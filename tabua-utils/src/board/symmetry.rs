@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+
+use super::grid::{CellType, Grid, GridExt, Position2d};
+
+/// Symmetries of a rectangular board, rotations are clockwise.
+///
+/// They move cells as laid out in rows and columns, so they only keep the
+/// adjacency of square grids.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left and right
+    FlipHorizontal,
+    /// Mirror top and bottom
+    FlipVertical,
+    /// Mirror along the main diagonal
+    Transpose,
+    /// Mirror along the anti-diagonal
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => *other,
+        }
+    }
+
+    /// Whether the transform swaps the number of rows and columns
+    pub fn swaps_dimensions(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    /// Where `position` of a `rows` x `columns` board ends up after the transform
+    pub fn apply(&self, position: &Position2d, rows: usize, columns: usize) -> Position2d {
+        let (row, col) = *position;
+        match self {
+            Symmetry::Identity => (row, col),
+            Symmetry::Rotate90 => (col, rows - 1 - row),
+            Symmetry::Rotate180 => (rows - 1 - row, columns - 1 - col),
+            Symmetry::Rotate270 => (columns - 1 - col, row),
+            Symmetry::FlipHorizontal => (row, columns - 1 - col),
+            Symmetry::FlipVertical => (rows - 1 - row, col),
+            Symmetry::Transpose => (col, row),
+            Symmetry::AntiTranspose => (columns - 1 - col, rows - 1 - row),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// # Panics
+    ///
+    /// If the cells aren't [`CellType::Square`], as are the helpers below
+    pub fn transformed(&self, symmetry: Symmetry) -> Grid<T> {
+        assert_eq!(
+            self.cell_type(),
+            CellType::Square,
+            "symmetries only apply to square cells"
+        );
+        let (rows, columns) = if symmetry.swaps_dimensions() {
+            (self.column_len(), self.row_len())
+        } else {
+            (self.row_len(), self.column_len())
+        };
+
+        let inverse = symmetry.inverse();
+        let cells = (0..rows)
+            .flat_map(|row| (0..columns).map(move |col| (row, col)))
+            .map(|position| {
+                let source = inverse.apply(&position, rows, columns);
+                self.get(&source).expect("inside the board").clone()
+            })
+            .collect();
+
+        let grid = self.with_cells(rows, columns, cells);
+        if symmetry.swaps_dimensions() {
+            grid.with_topology(self.topology().transposed())
+        } else {
+            grid
+        }
+    }
+
+    pub fn rotate_90(&self) -> Grid<T> {
+        self.transformed(Symmetry::Rotate90)
+    }
+
+    pub fn rotate_180(&self) -> Grid<T> {
+        self.transformed(Symmetry::Rotate180)
+    }
+
+    pub fn rotate_270(&self) -> Grid<T> {
+        self.transformed(Symmetry::Rotate270)
+    }
+
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        self.transformed(Symmetry::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(&self) -> Grid<T> {
+        self.transformed(Symmetry::FlipVertical)
+    }
+
+    pub fn transpose(&self) -> Grid<T> {
+        self.transformed(Symmetry::Transpose)
+    }
+
+    /// Lexicographically smallest variant of the board, comparing row by row,
+    /// with the symmetry producing it.
+    ///
+    /// Non square boards only consider the symmetries keeping their shape.
+    ///
+    /// # Panics
+    ///
+    /// If the cells aren't [`CellType::Square`]
+    pub fn canonical(&self) -> (Grid<T>, Symmetry)
+    where
+        T: Ord,
+    {
+        let square = self.row_len() == self.column_len();
+        Symmetry::ALL
+            .into_iter()
+            .filter(|symmetry| square || !symmetry.swaps_dimensions())
+            .map(|symmetry| (self.transformed(symmetry), symmetry))
+            .min_by(|(a, _), (b, _)| a.rows().cmp(b.rows()))
+            .expect("identity is always a candidate")
+    }
+
+    /// Transform in place the `size` x `size` block starting at `top_left`,
+    /// like turning a Pentago quadrant
+    ///
+    /// # Panics
+    ///
+    /// If the block goes past the board or the cells aren't [`CellType::Square`]
+    pub fn transform_block(&mut self, top_left: &Position2d, size: usize, symmetry: Symmetry) {
+        let block = self
+            .view(top_left, size, size)
+            .expect("block out of the board")
+            .to_grid()
+            .transformed(symmetry);
+        self.view_mut(top_left, size, size)
+            .expect("block out of the board")
+            .copy_from(&block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{GridBuilder, Topology};
+
+    fn board(rows: &[&str]) -> Grid<char> {
+        Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn rotations() {
+        let grid = board(&["abc", "def"]);
+
+        assert_eq!(grid.rotate_90(), board(&["da", "eb", "fc"]));
+        assert_eq!(grid.rotate_180(), board(&["fed", "cba"]));
+        assert_eq!(grid.rotate_270(), board(&["cf", "be", "ad"]));
+        assert_eq!(grid.rotate_90().rotate_90().rotate_90().rotate_90(), grid);
+    }
+
+    #[test]
+    fn reflections() {
+        let grid = board(&["abc", "def"]);
+
+        assert_eq!(grid.flip_horizontal(), board(&["cba", "fed"]));
+        assert_eq!(grid.flip_vertical(), board(&["def", "abc"]));
+        assert_eq!(grid.transpose(), board(&["ad", "be", "cf"]));
+        assert_eq!(
+            grid.transformed(Symmetry::AntiTranspose),
+            board(&["fc", "eb", "da"])
+        );
+    }
+
+    #[test]
+    fn apply_matches_transformed() {
+        let grid = board(&["abc", "def"]);
+        for symmetry in Symmetry::ALL {
+            let transformed = grid.transformed(symmetry);
+            let position = symmetry.apply(&(0, 1), 2, 3);
+            assert_eq!(transformed.get(&position), Some(&'b'));
+            assert_eq!(
+                grid.transformed(symmetry).transformed(symmetry.inverse()),
+                grid
+            );
+        }
+    }
+
+    #[test]
+    fn rotation_swaps_wrapped_axis() {
+        let grid: Grid<()> = GridBuilder::new_square_grid()
+            .with_rows(3)
+            .with_columns(4)
+            .with_initial_value(())
+            .with_topology(Topology::WrapColumns)
+            .build();
+        let rotated = grid.rotate_90();
+
+        // (0, 0) and (0, 3) are joined across the seam, they end up at (0, 2) and (3, 2)
+        assert_eq!(rotated.topology(), Topology::WrapRows);
+        assert!(rotated
+            .adjacent_cells(&(0, 2))
+            .iter()
+            .any(|(p, _)| *p == (3, 2)));
+        assert_eq!(grid.flip_horizontal().topology(), Topology::WrapColumns);
+        assert_eq!(rotated.rotate_270(), grid);
+    }
+
+    #[test]
+    fn canonical() {
+        let grid = board(&["x..", ".o.", "..."]);
+        let (canonical, _) = grid.canonical();
+
+        for symmetry in Symmetry::ALL {
+            assert_eq!(grid.transformed(symmetry).canonical().0, canonical);
+        }
+
+        let (canonical, symmetry) = board(&["...", ".o.", "..x"]).canonical();
+        assert_eq!(canonical, board(&["...", ".o.", "..x"]));
+        assert_eq!(symmetry, Symmetry::Identity);
+    }
+
+    #[test]
+    fn canonical_keeps_shape() {
+        let (canonical, _) = board(&["ba", "cc", "dd"]).canonical();
+        assert_eq!(canonical, board(&["ab", "cc", "dd"]));
+    }
+
+    #[test]
+    fn pentago_quadrant() {
+        let mut grid = board(&["ab..", "cd..", "....", "...."]);
+        grid.transform_block(&(0, 0), 2, Symmetry::Rotate90);
+
+        assert_eq!(grid, board(&["ca..", "db..", "....", "...."]));
+    }
+
+    #[test]
+    #[should_panic(expected = "symmetries only apply to square cells")]
+    fn hex_cells() {
+        Grid::new_hex_grid(vec![vec![1, 2], vec![3, 4]]).canonical();
+    }
+
+    #[test]
+    #[should_panic(expected = "block out of the board")]
+    fn block_out_of_the_board() {
+        board(&["ab", "cd"]).transform_block(&(1, 1), 2, Symmetry::Rotate90);
+    }
+}
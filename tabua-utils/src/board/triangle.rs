@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+
+use super::grid::{CellAdjacency, Position2d, Position2dDiff};
+
+/// Coordinate of a triangle of a triangular tiling.
+///
+/// In offset `(row, column)` positions triangles alternate along rows, the
+/// ones with an even `row + column` point up. Each coordinate counts the
+/// lines of one of the three edge directions, up triangles have `a + b + c == 2`
+/// and down triangles `a + b + c == 1`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct TriCoord {
+    pub a: i32,
+    pub b: i32,
+    pub c: i32,
+}
+
+impl TriCoord {
+    pub fn new(a: i32, b: i32, c: i32) -> Option<Self> {
+        matches!(a + b + c, 1 | 2).then_some(Self { a, b, c })
+    }
+
+    pub fn from_offset(position: &Position2d) -> Self {
+        Self::from_signed_offset((position.0 as i32, position.1 as i32))
+    }
+
+    pub fn from_signed_offset((row, col): (i32, i32)) -> Self {
+        Self {
+            a: 1 - row,
+            b: (col + row + 2).div_euclid(2),
+            c: -(col - row + 1).div_euclid(2),
+        }
+    }
+
+    /// Offset position of the coordinate, `None` if it falls at negative rows or columns
+    pub fn to_offset(&self) -> Option<Position2d> {
+        let (row, col) = self.to_signed_offset();
+        Some((row.try_into().ok()?, col.try_into().ok()?))
+    }
+
+    /// Offset row and column of the coordinate, which may be negative
+    pub fn to_signed_offset(&self) -> (i32, i32) {
+        (1 - self.a, self.b - self.c - 1)
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.a + self.b + self.c == 2
+    }
+
+    /// Number of steps between two triangles through shared sides
+    pub fn distance(&self, other: &TriCoord) -> usize {
+        ((self.a - other.a).abs() + (self.b - other.b).abs() + (self.c - other.c).abs()) as usize
+    }
+
+    /// Number of steps between two triangles through shared sides or vertices
+    pub fn vertex_distance(&self, other: &TriCoord) -> usize {
+        (self.a - other.a)
+            .abs()
+            .max((self.b - other.b).abs())
+            .max((self.c - other.c).abs()) as usize
+    }
+
+    /// Offsets of the triangles adjacent to this one, they depend on which way it points
+    pub fn adjacencies(&self, adjacency: CellAdjacency) -> Vec<Position2dDiff> {
+        // rows towards the flat side and towards the tip, down triangles are mirrored
+        let (base, tip) = if self.is_up() { (1, -1) } else { (-1, 1) };
+        match adjacency {
+            CellAdjacency::Side => vec![(0, -1), (0, 1), (base, 0)],
+            #[rustfmt::skip]
+            CellAdjacency::SideAndVertex => vec![
+                (tip, -1), (tip, 0), (tip, 1),
+                (0, -2), (0, -1), (0, 1), (0, 2),
+                (base, -2), (base, -1), (base, 0), (base, 1), (base, 2),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{Grid, GridBuilder, GridExt};
+    use crate::pathfinder::bfs::BreadthFirstSearch;
+
+    fn triangle_grid(adjacency: CellAdjacency) -> Grid<()> {
+        GridBuilder::new_triangle_grid()
+            .with_cell_adjacency(adjacency)
+            .with_rows(6)
+            .with_columns(9)
+            .with_initial_value(())
+            .build()
+    }
+
+    fn neighbors(grid: &Grid<()>, position: Position2d) -> Vec<Position2d> {
+        let mut neighbors: Vec<_> = grid
+            .adjacent_cells(&position)
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        for row in -4..4 {
+            for col in -4..4 {
+                let coord = TriCoord::from_signed_offset((row, col));
+                assert_eq!(coord.to_signed_offset(), (row, col));
+                assert_eq!(coord.is_up(), (row + col) % 2 == 0);
+                assert_eq!(TriCoord::new(coord.a, coord.b, coord.c), Some(coord));
+            }
+        }
+        assert_eq!(TriCoord::new(1, 1, 1), None);
+        assert_eq!(TriCoord::from_signed_offset((-1, 0)).to_offset(), None);
+    }
+
+    #[test]
+    fn side_adjacency() {
+        let grid = triangle_grid(CellAdjacency::Side);
+
+        // up triangle, its flat side faces the row below
+        assert_eq!(neighbors(&grid, (2, 2)), vec![(2, 1), (2, 3), (3, 2)]);
+        // down triangle, its flat side faces the row above
+        assert_eq!(neighbors(&grid, (2, 3)), vec![(1, 3), (2, 2), (2, 4)]);
+        assert_eq!(neighbors(&grid, (0, 0)), vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn vertex_adjacency() {
+        let grid = triangle_grid(CellAdjacency::SideAndVertex);
+
+        assert_eq!(neighbors(&grid, (2, 4)).len(), 12);
+        assert_eq!(
+            neighbors(&grid, (2, 3)),
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (1, 4),
+                (1, 5),
+                (2, 1),
+                (2, 2),
+                (2, 4),
+                (2, 5),
+                (3, 2),
+                (3, 3),
+                (3, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn distance_matches_bfs() {
+        for adjacency in [CellAdjacency::Side, CellAdjacency::SideAndVertex] {
+            let grid = triangle_grid(adjacency);
+            // an up and a down triangle, their neighbors lie on different rows
+            for from in [(2, 4), (2, 3)] {
+                for row in 0..grid.row_len() {
+                    for col in 0..grid.column_len() {
+                        let steps = grid.bfs(&from, &(row, col)).unwrap().len() - 1;
+                        assert_eq!(grid.tri_distance(&from, &(row, col)), Some(steps));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn coordinates() {
+        let grid = triangle_grid(CellAdjacency::Side);
+        let coord = grid.tri_coord(&(3, 5)).unwrap();
+
+        assert_eq!(grid.tri_position(&coord), Some((3, 5)));
+        assert_eq!(
+            grid.tri_position(&TriCoord::from_signed_offset((6, 0))),
+            None
+        );
+        assert_eq!(
+            Grid::new_square_grid(vec![vec![()]]).tri_coord(&(0, 0)),
+            None
+        );
+    }
+}
@@ -0,0 +1,355 @@
+use super::grid::{
+    AddTuple, ColumnCellIterator, ColumnIterator, Grid, GridExt, GridExtMut, Position2d,
+    Position2dDiff, RowCellIterator,
+};
+
+/// Rectangle of a grid, positions inside it start at its top left cell
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Area {
+    top_left: Position2d,
+    rows: usize,
+    columns: usize,
+}
+
+impl Area {
+    fn new<T>(grid: &Grid<T>, top_left: &Position2d, rows: usize, columns: usize) -> Option<Self> {
+        let bottom = top_left.0.checked_add(rows)?;
+        let right = top_left.1.checked_add(columns)?;
+        (bottom <= grid.row_len() && right <= grid.column_len()).then_some(Self {
+            top_left: *top_left,
+            rows,
+            columns,
+        })
+    }
+
+    fn contains(&self, position: &Position2d) -> bool {
+        position.0 < self.rows && position.1 < self.columns
+    }
+
+    fn offset(&self, position: &Position2d) -> Position2d {
+        (self.top_left.0 + position.0, self.top_left.1 + position.1)
+    }
+
+    fn grid_position(&self, position: &Position2d) -> Option<Position2d> {
+        self.contains(position).then(|| self.offset(position))
+    }
+
+    /// Cells of `row` of the area, `None` outside it
+    fn row<'a, T>(&self, grid: &'a Grid<T>, row: usize) -> Option<&'a [T]> {
+        let (top, left) = self.top_left;
+        let cells = grid.row(top + row).filter(|_| row < self.rows)?;
+        Some(&cells[left..left + self.columns])
+    }
+}
+
+/// Rows of a [`GridView`] or [`GridViewMut`], see [`GridExt::rows`]
+pub struct ViewRows<'a, T> {
+    grid: &'a Grid<T>,
+    area: Area,
+    row: usize,
+}
+
+impl<'a, T> Iterator for ViewRows<'a, T> {
+    type Item = &'a [T];
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.area.row(self.grid, self.row)?;
+        self.row += 1;
+        Some(result)
+    }
+}
+
+/// Both views read their grid through their area the same way
+macro_rules! grid_ext_for_view {
+    ($view:ident) => {
+        impl<'a, 'g: 'a, T: 'a> GridExt<'a, T> for $view<'g, T> {
+            type RowCells = RowCellIterator<'a, T>;
+            type Rows = ViewRows<'a, T>;
+
+            /// Empty when `row` is outside the view
+            fn iter_row(&'a self, row: usize) -> Self::RowCells {
+                self.area.row(self.grid, row).unwrap_or_default().iter()
+            }
+            fn iter_column(&'a self, col: usize) -> ColumnCellIterator<'a, T> {
+                ColumnCellIterator::new(self, col)
+            }
+
+            fn row_len(&self) -> usize {
+                self.area.rows
+            }
+            fn column_len(&self) -> usize {
+                self.area.columns
+            }
+
+            fn rows(&'a self) -> Self::Rows {
+                ViewRows {
+                    grid: self.grid,
+                    area: self.area,
+                    row: 0,
+                }
+            }
+            fn columns(&'a self) -> ColumnIterator<'a, T> {
+                ColumnIterator::new(self)
+            }
+
+            fn get(&'a self, position: &Position2d) -> Option<&'a T> {
+                self.grid.get(&self.area.grid_position(position)?)
+            }
+
+            fn adjacencies(&self, position: &Position2d) -> Vec<Position2dDiff> {
+                self.grid.adjacencies(&self.area.offset(position))
+            }
+
+            fn adjacent_cells(&'a self, current: &Position2d) -> Vec<(Position2d, &'a T)> {
+                self.adjacencies(current)
+                    .into_iter()
+                    .filter_map(|diff| current.add(diff))
+                    .filter_map(|p| Some((p, self.get(&p)?)))
+                    .collect()
+            }
+        }
+    };
+}
+
+/// Borrowed rectangle of a [`Grid`], like a Sudoku box or a Pentago quadrant.
+///
+/// Its positions start at its top left cell, adjacency follows the grid's
+/// cell type but never wraps out of the view. It is read only, see
+/// [`GridViewMut`] to change cells.
+pub struct GridView<'g, T> {
+    grid: &'g Grid<T>,
+    area: Area,
+}
+
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'g, T> GridView<'g, T> {
+    pub fn top_left(&self) -> Position2d {
+        self.area.top_left
+    }
+
+    /// Position in the whole grid of a position of the view
+    pub fn grid_position(&self, position: &Position2d) -> Option<Position2d> {
+        self.area.grid_position(position)
+    }
+
+    /// Copy of the viewed cells, keeping the grid cell type and adjacency
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = self.rows().flatten().cloned().collect();
+        self.grid
+            .with_cells(self.area.rows, self.area.columns, cells)
+    }
+}
+
+grid_ext_for_view!(GridView);
+
+/// Mutably borrowed rectangle of a [`Grid`], see [`GridView`]
+pub struct GridViewMut<'g, T> {
+    grid: &'g mut Grid<T>,
+    area: Area,
+}
+
+impl<'g, T> GridViewMut<'g, T> {
+    pub fn top_left(&self) -> Position2d {
+        self.area.top_left
+    }
+
+    /// Position in the whole grid of a position of the view
+    pub fn grid_position(&self, position: &Position2d) -> Option<Position2d> {
+        self.area.grid_position(position)
+    }
+
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            area: self.area,
+        }
+    }
+
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        let (top, left) = self.area.top_left;
+        for row in top..top + self.area.rows {
+            let cells = self.grid.row_mut(row).expect("inside the board");
+            cells[left..left + self.area.columns].fill(value.clone());
+        }
+    }
+
+    /// Overwrite the viewed cells with the cells of `grid`, which must have the view size
+    pub fn copy_from(&mut self, grid: &Grid<T>)
+    where
+        T: Clone,
+    {
+        assert_eq!(
+            (grid.row_len(), grid.column_len()),
+            (self.area.rows, self.area.columns),
+            "grid of the view size"
+        );
+
+        for (row, cells) in grid.rows().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                *self.get_mut(&(row, col)).expect("inside the view") = cell.clone();
+            }
+        }
+    }
+}
+
+grid_ext_for_view!(GridViewMut);
+
+impl<'a, 'g: 'a, T: 'a> GridExtMut<'a, T> for GridViewMut<'g, T> {
+    fn get_mut(&'a mut self, position: &Position2d) -> Option<&'a mut T> {
+        self.grid.get_mut(&self.area.grid_position(position)?)
+    }
+}
+
+impl<T> Grid<T> {
+    /// View of the `rows` x `columns` rectangle starting at `top_left`, `None`
+    /// if it does not fit in the grid
+    pub fn view(
+        &self,
+        top_left: &Position2d,
+        rows: usize,
+        columns: usize,
+    ) -> Option<GridView<'_, T>> {
+        Some(GridView {
+            area: Area::new(self, top_left, rows, columns)?,
+            grid: self,
+        })
+    }
+
+    pub fn view_mut(
+        &mut self,
+        top_left: &Position2d,
+        rows: usize,
+        columns: usize,
+    ) -> Option<GridViewMut<'_, T>> {
+        Some(GridViewMut {
+            area: Area::new(self, top_left, rows, columns)?,
+            grid: self,
+        })
+    }
+
+    /// Every `rows` x `columns` view, sliding one cell at a time, row after row
+    pub fn windows(&self, rows: usize, columns: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        let (last_row, last_column) = (
+            (self.row_len() + 1).saturating_sub(rows),
+            (self.column_len() + 1).saturating_sub(columns),
+        );
+        (0..last_row)
+            .flat_map(move |row| (0..last_column).map(move |col| (row, col)))
+            .filter_map(move |top_left| self.view(&top_left, rows, columns))
+    }
+
+    /// Views tiling the grid with `rows` x `columns` blocks, row after row, like
+    /// the boxes of a Sudoku. Cells past the last whole block are left out.
+    ///
+    /// # Panics
+    ///
+    /// If `rows` or `columns` is zero
+    pub fn blocks(&self, rows: usize, columns: usize) -> impl Iterator<Item = GridView<'_, T>> {
+        assert!(rows > 0 && columns > 0, "blocks can't be empty");
+        (0..self.row_len() / rows)
+            .flat_map(move |row| (0..self.column_len() / columns).map(move |col| (row, col)))
+            .filter_map(move |(row, col)| self.view(&(row * rows, col * columns), rows, columns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::{GridBuilder, Topology};
+
+    fn board(rows: &[&str]) -> Grid<char> {
+        Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    fn text<'a>(view: &'a impl GridExt<'a, char>) -> Vec<String> {
+        view.rows().map(|row| row.into_iter().collect()).collect()
+    }
+
+    #[test]
+    fn view() {
+        let grid = board(&["abcd", "efgh", "ijkl"]);
+        let view = grid.view(&(1, 1), 2, 2).unwrap();
+
+        assert_eq!(text(&view), vec!["fg", "jk"]);
+        assert_eq!(view.get(&(1, 0)), Some(&'j'));
+        assert_eq!(view.get(&(0, 2)), None);
+        assert_eq!(view.grid_position(&(1, 1)), Some((2, 2)));
+        assert_eq!(view.iter_column(1).collect::<String>(), "gk");
+        assert_eq!(view.to_grid(), board(&["fg", "jk"]));
+
+        assert!(grid.view(&(1, 1), 2, 4).is_none());
+        assert!(grid.view(&(1, 1), usize::MAX, 1).is_none());
+        assert!(grid.view(&(usize::MAX, 0), 1, 1).is_none());
+    }
+
+    #[test]
+    fn adjacency_stays_in_view() {
+        let grid = GridBuilder::new_square_grid()
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+        let view = grid.view(&(1, 1), 2, 2).unwrap();
+
+        let mut neighbors: Vec<_> = view
+            .adjacent_cells(&(0, 0))
+            .into_iter()
+            .map(|(p, _)| p)
+            .collect();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn view_mut() {
+        let mut grid = board(&["abc", "def", "ghi"]);
+        let mut view = grid.view_mut(&(0, 1), 2, 2).unwrap();
+
+        *view.get_mut(&(1, 1)).unwrap() = 'x';
+        assert_eq!(text(&view), vec!["bc", "ex"]);
+        view.fill('.');
+        assert_eq!(grid, board(&["a..", "d..", "ghi"]));
+
+        let mut view = grid.view_mut(&(1, 0), 2, 2).unwrap();
+        view.copy_from(&board(&["12", "34"]));
+        assert_eq!(grid, board(&["a..", "12.", "34i"]));
+    }
+
+    #[test]
+    fn windows() {
+        let grid = board(&["abc", "def", "ghi"]);
+        let windows: Vec<_> = grid.windows(2, 2).map(|view| text(&view)).collect();
+
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[3], vec!["ef", "hi"]);
+        assert_eq!(grid.windows(1, 3).count(), 3);
+        assert_eq!(grid.windows(4, 1).count(), 0);
+    }
+
+    #[test]
+    fn sudoku_boxes() {
+        let grid = Grid::from_cells(9, 9, (0..81).collect()).unwrap();
+        let boxes: Vec<_> = grid.blocks(3, 3).collect();
+
+        assert_eq!(boxes.len(), 9);
+        assert_eq!(boxes[4].top_left(), (3, 3));
+        assert_eq!(
+            boxes[4].rows().flatten().copied().collect::<Vec<_>>(),
+            vec![30, 31, 32, 39, 40, 41, 48, 49, 50]
+        );
+        assert_eq!(board(&["abcde", "fghij"]).blocks(2, 2).count(), 2);
+    }
+}
@@ -0,0 +1,157 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::grid::{Grid, GridExt, Position2d};
+
+/// Random keys to hash boards by xoring one key per occupied cell.
+///
+/// Pieces are identified by a small index, so placing, removing or moving a
+/// piece updates a hash with a couple of [`Zobrist::toggle`] calls instead of
+/// hashing the whole board again.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Zobrist {
+    rows: usize,
+    columns: usize,
+    pieces: usize,
+    keys: Vec<u64>,
+    side_to_move: u64,
+}
+
+impl Zobrist {
+    /// Keys for a `rows` x `columns` board holding `pieces` kinds of piece,
+    /// the same seed always produces the same keys
+    pub fn new(rows: usize, columns: usize, pieces: usize, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        Self {
+            rows,
+            columns,
+            pieces,
+            keys: (0..rows * columns * pieces).map(|_| rng.gen()).collect(),
+            side_to_move: rng.gen(),
+        }
+    }
+
+    /// Keys sized after `grid`
+    pub fn for_grid<T>(grid: &Grid<T>, pieces: usize, seed: u64) -> Self {
+        Self::new(grid.row_len(), grid.column_len(), pieces, seed)
+    }
+
+    /// Key of `piece` standing on `position`
+    ///
+    /// # Panics
+    ///
+    /// If `position` is outside the board or `piece` is not below the number of pieces
+    pub fn key(&self, position: &Position2d, piece: usize) -> u64 {
+        assert!(
+            position.0 < self.rows && position.1 < self.columns,
+            "position out of the board"
+        );
+        assert!(piece < self.pieces, "unknown piece {piece}");
+
+        self.keys[(position.0 * self.columns + position.1) * self.pieces + piece]
+    }
+
+    /// Key to xor in when a position hash should depend on who moves
+    pub fn side_to_move(&self) -> u64 {
+        self.side_to_move
+    }
+
+    /// Hash of a whole board, `piece` maps a cell to its piece index or `None` when empty
+    ///
+    /// # Panics
+    ///
+    /// If `grid` is larger than the keys' board, or `piece` gives an unknown piece
+    pub fn hash<T, F>(&self, grid: &Grid<T>, piece: F) -> u64
+    where
+        F: Fn(&T) -> Option<usize>,
+    {
+        assert!(
+            grid.row_len() <= self.rows && grid.column_len() <= self.columns,
+            "{}x{} grid larger than the {}x{} keys",
+            grid.row_len(),
+            grid.column_len(),
+            self.rows,
+            self.columns
+        );
+
+        let mut hash = 0;
+        for (row, cells) in grid.rows().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if let Some(piece) = piece(cell) {
+                    hash ^= self.key(&(row, col), piece);
+                }
+            }
+        }
+        hash
+    }
+
+    /// Add or remove `piece` at `position` from `hash`
+    pub fn toggle(&self, hash: u64, position: &Position2d, piece: usize) -> u64 {
+        hash ^ self.key(position, piece)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piece(cell: &char) -> Option<usize> {
+        match cell {
+            'x' => Some(0),
+            'o' => Some(1),
+            _ => None,
+        }
+    }
+
+    fn board(rows: &[&str]) -> Grid<char> {
+        Grid::new_square_grid(rows.iter().map(|row| row.chars().collect()).collect())
+    }
+
+    #[test]
+    fn incremental_matches_full_hash() {
+        let zobrist = Zobrist::new(3, 3, 2, 7);
+        let empty = zobrist.hash(&board(&["...", "...", "..."]), piece);
+        assert_eq!(empty, 0);
+
+        let hash = zobrist.toggle(empty, &(1, 1), 0);
+        let hash = zobrist.toggle(hash, &(0, 2), 1);
+        assert_eq!(hash, zobrist.hash(&board(&["..o", ".x.", "..."]), piece));
+
+        // moving the x away
+        let hash = zobrist.toggle(hash, &(1, 1), 0);
+        let hash = zobrist.toggle(hash, &(2, 0), 0);
+        assert_eq!(hash, zobrist.hash(&board(&["..o", "...", "x.."]), piece));
+    }
+
+    #[test]
+    fn different_boards_differ() {
+        let zobrist = Zobrist::new(3, 3, 2, 7);
+
+        assert_ne!(
+            zobrist.hash(&board(&["x..", "...", "..."]), piece),
+            zobrist.hash(&board(&["o..", "...", "..."]), piece)
+        );
+        assert_ne!(
+            zobrist.hash(&board(&["x..", "...", "..."]), piece),
+            zobrist.hash(&board(&[".x.", "...", "..."]), piece)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "grid larger than the 3x3 keys")]
+    fn grid_larger_than_keys() {
+        Zobrist::new(3, 3, 2, 7).hash(&board(&["....", "....", "...."]), piece);
+    }
+
+    #[test]
+    fn seeded_keys() {
+        let grid = board(&["xo.", "...", "..."]);
+        assert_eq!(Zobrist::for_grid(&grid, 2, 1), Zobrist::new(3, 3, 2, 1));
+        assert_ne!(Zobrist::new(3, 3, 2, 1), Zobrist::new(3, 3, 2, 2));
+
+        let zobrist = Zobrist::new(3, 3, 2, 1);
+        let json = serde_json::to_string(&zobrist).unwrap();
+        assert_eq!(serde_json::from_str::<Zobrist>(&json).unwrap(), zobrist);
+    }
+}
@@ -1,5 +1,7 @@
 use rand::prelude::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 pub trait Deck {
     type TokenType;
@@ -17,12 +19,18 @@ pub trait Deck {
     fn put_random(&mut self, token: Self::TokenType);
 }
 
+/// Random deck operations driven by a caller provided random number generator
+pub trait DeckWithRng: Deck {
+    fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R);
+    fn draw_random_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Self::TokenType>;
+    fn put_random_with<R: Rng + ?Sized>(&mut self, token: Self::TokenType, rng: &mut R);
+}
+
 impl<T> Deck for Vec<T> {
     type TokenType = T;
 
     fn shuffle(&mut self) {
-        let mut rng = rand::thread_rng();
-        self.as_mut_slice().shuffle(&mut rng);
+        self.shuffle_with(&mut rand::thread_rng());
     }
 
     fn draw(&mut self) -> Option<Self::TokenType> {
@@ -30,13 +38,7 @@ impl<T> Deck for Vec<T> {
     }
 
     fn draw_random(&mut self) -> Option<Self::TokenType> {
-        if self.is_empty() {
-            return None;
-        }
-
-        let mut rng = rand::thread_rng();
-        let n: usize = rng.gen_range(0..self.len());
-        Some(self.remove(n))
+        self.draw_random_with(&mut rand::thread_rng())
     }
 
     fn peek(&self, n: usize) -> Vec<&Self::TokenType> {
@@ -56,8 +58,147 @@ impl<T> Deck for Vec<T> {
     }
 
     fn put_random(&mut self, token: Self::TokenType) {
-        let mut rng = rand::thread_rng();
+        self.put_random_with(token, &mut rand::thread_rng());
+    }
+}
+
+impl<T> DeckWithRng for Vec<T> {
+    fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.as_mut_slice().shuffle(rng);
+    }
+
+    fn draw_random_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Self::TokenType> {
+        if self.is_empty() {
+            return None;
+        }
+
         let n: usize = rng.gen_range(0..self.len());
+        Some(self.remove(n))
+    }
+
+    fn put_random_with<R: Rng + ?Sized>(&mut self, token: Self::TokenType, rng: &mut R) {
+        let n: usize = rng.gen_range(0..=self.len());
         self.insert(n, token);
     }
 }
+
+/// Deck owning its random number generator.
+///
+/// The generator state is serialized with the deck, so a game storing it in
+/// its state replays the exact same draws and shuffles after being restored.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SeededDeck<T> {
+    tokens: Vec<T>,
+    rng: ChaCha8Rng,
+}
+
+impl<T> SeededDeck<T> {
+    pub fn new(tokens: Vec<T>, seed: u64) -> Self {
+        Self {
+            tokens,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn tokens(&self) -> &[T] {
+        &self.tokens
+    }
+
+    pub fn into_tokens(self) -> Vec<T> {
+        self.tokens
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+impl<T> Deck for SeededDeck<T> {
+    type TokenType = T;
+
+    fn shuffle(&mut self) {
+        self.tokens.shuffle_with(&mut self.rng);
+    }
+
+    fn draw(&mut self) -> Option<Self::TokenType> {
+        self.tokens.draw()
+    }
+
+    fn draw_random(&mut self) -> Option<Self::TokenType> {
+        self.tokens.draw_random_with(&mut self.rng)
+    }
+
+    fn peek(&self, n: usize) -> Vec<&Self::TokenType> {
+        self.tokens.peek(n)
+    }
+
+    fn peek_top(&self) -> Option<&Self::TokenType> {
+        self.tokens.peek_top()
+    }
+
+    fn put_top(&mut self, token: Self::TokenType) {
+        self.tokens.put_top(token)
+    }
+
+    fn put_bottom(&mut self, token: Self::TokenType) {
+        self.tokens.put_bottom(token)
+    }
+
+    fn put_random(&mut self, token: Self::TokenType) {
+        self.tokens.put_random_with(token, &mut self.rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_shuffle() {
+        let mut first = SeededDeck::new((0..52).collect::<Vec<_>>(), 7);
+        let mut second = SeededDeck::new((0..52).collect::<Vec<_>>(), 7);
+
+        first.shuffle();
+        second.shuffle();
+        assert_eq!(first.tokens(), second.tokens());
+        assert_ne!(first.tokens(), (0..52).collect::<Vec<_>>());
+
+        assert_eq!(first.draw_random(), second.draw_random());
+    }
+
+    #[test]
+    fn rng_state_survives_serialization() {
+        let mut deck = SeededDeck::new((0..52).collect::<Vec<_>>(), 7);
+        deck.shuffle();
+
+        let mut restored: SeededDeck<i32> =
+            serde_json::from_str(&serde_json::to_string(&deck).unwrap()).unwrap();
+        assert_eq!(restored, deck);
+
+        deck.shuffle();
+        restored.shuffle();
+        assert_eq!(restored.tokens(), deck.tokens());
+    }
+
+    #[test]
+    fn vec_with_rng() {
+        let mut first: Vec<_> = (0..10).collect();
+        let mut second = first.clone();
+
+        first.shuffle_with(&mut ChaCha8Rng::seed_from_u64(1));
+        second.shuffle_with(&mut ChaCha8Rng::seed_from_u64(1));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn put_random_in_empty_deck() {
+        let mut deck = SeededDeck::new(vec![], 0);
+        deck.put_random(1);
+
+        assert_eq!(deck.tokens(), &[1]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::board::neighbors::Neighbors;
+
+pub trait AStar: Neighbors {
+    /// Cheapest path from `root` to `goal` and its total cost.
+    ///
+    /// `cost` gives the price of moving between two adjacent cells, receiving
+    /// the destination cell, or `None` when the move is impossible.
+    /// `heuristic` must never overestimate the remaining cost to `goal`.
+    /// Paths whose cost overflows a `u32` are left out.
+    fn a_star<C, H>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        cost: C,
+        heuristic: H,
+    ) -> Option<(Vec<Self::Position>, u32)>
+    where
+        C: Fn(&Self::Position, &Self::Position, &Self::Cell) -> Option<u32>,
+        H: Fn(&Self::Position, &Self::Position) -> u32;
+}
+
+impl<B: Neighbors> AStar for B {
+    fn a_star<C, H>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        cost: C,
+        heuristic: H,
+    ) -> Option<(Vec<Self::Position>, u32)>
+    where
+        C: Fn(&Self::Position, &Self::Position, &Self::Cell) -> Option<u32>,
+        H: Fn(&Self::Position, &Self::Position) -> u32,
+    {
+        self.cell(root)?;
+        // cheapest known cost to each position and where it comes from
+        let mut best = HashMap::from([(*root, (0, *root))]);
+        let mut open = BinaryHeap::from([Reverse((heuristic(root, goal), 0, *root))]);
+        while let Some(Reverse((_, spent, current))) = open.pop() {
+            if current == *goal {
+                let mut path = vec![current];
+                while let Some(&(_, previous)) = best.get(path.last().unwrap()) {
+                    if previous == *path.last().unwrap() {
+                        break;
+                    }
+                    path.push(previous);
+                }
+                path.reverse();
+
+                return Some((path, spent));
+            }
+
+            if matches!(best.get(&current), Some((known, _)) if *known < spent) {
+                continue;
+            }
+
+            for (next, cell) in self.neighbors(&current) {
+                let Some(step) = cost(&current, &next, cell) else {
+                    continue;
+                };
+
+                let Some(total) = spent.checked_add(step) else {
+                    continue;
+                };
+                if matches!(best.get(&next), Some((known, _)) if *known <= total) {
+                    continue;
+                }
+
+                best.insert(next, (total, current));
+                open.push(Reverse((
+                    total.saturating_add(heuristic(&next, goal)),
+                    total,
+                    next,
+                )));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::grid::{CellAdjacency, Grid, GridBuilder, Position2d, Topology};
+    use crate::board::hex::HexLayout;
+    use crate::pathfinder::heuristic::{
+        chebyshev, hex_distance, manhattan, wrapped_chebyshev, wrapped_manhattan,
+    };
+
+    use super::*;
+
+    fn uniform<T>(_: &Position2d, _: &Position2d, _: &T) -> Option<u32> {
+        Some(1)
+    }
+
+    #[test]
+    fn line_goal() {
+        let grid = Grid::new_square_grid(vec![vec![(); 4]; 4]);
+
+        let root = (0, 0);
+        let goal = (0, 3);
+
+        assert_eq!(
+            grid.a_star(&root, &goal, uniform, manhattan),
+            Some((vec![root, (0, 1), (0, 2), goal], 3))
+        )
+    }
+
+    #[test]
+    fn around_the_wall() {
+        #[rustfmt::skip]
+        let grid = Grid::new_square_grid(vec![
+            vec!['.', '#', '.'],
+            vec!['.', '#', '.'],
+            vec!['.', '.', '.'],
+        ]);
+
+        let root = (0, 0);
+        let goal = (0, 2);
+
+        let walkable = |_: &_, _: &_, cell: &char| (*cell != '#').then_some(1);
+        let (path, cost) = grid.a_star(&root, &goal, walkable, manhattan).unwrap();
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert!(!path.contains(&(0, 1)) && !path.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn diagonal_goal() {
+        let grid = GridBuilder::new_square_grid()
+            .with_cell_adjacency(CellAdjacency::SideAndVertex)
+            .with_rows(5)
+            .with_columns(5)
+            .with_initial_value(())
+            .build();
+
+        let (path, cost) = grid.a_star(&(0, 0), &(4, 4), uniform, chebyshev).unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path, vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+    }
+
+    #[test]
+    fn torus_goal() {
+        let grid = GridBuilder::new_square_grid()
+            .with_rows(5)
+            .with_columns(6)
+            .with_initial_value(())
+            .with_topology(Topology::Torus)
+            .build();
+
+        let heuristic = wrapped_manhattan(5, 6, Topology::Torus);
+        assert_eq!(heuristic(&(0, 0), &(4, 5)), 2);
+        let (path, cost) = grid.a_star(&(0, 0), &(4, 5), uniform, heuristic).unwrap();
+        assert_eq!(cost, 2);
+        assert_eq!(path.len(), 3);
+
+        let heuristic = wrapped_chebyshev(5, 6, Topology::WrapColumns);
+        assert_eq!(heuristic(&(0, 0), &(4, 5)), 4);
+        assert_eq!(heuristic(&(0, 0), &(1, 3)), 3);
+    }
+
+    #[test]
+    fn hex_goal() {
+        let grid = GridBuilder::new_hex_grid()
+            .with_rows(6)
+            .with_columns(6)
+            .with_initial_value(())
+            .build();
+
+        let root = (0, 0);
+        let goal = (5, 3);
+
+        let (_, cost) = grid
+            .a_star(&root, &goal, uniform, hex_distance(HexLayout::OddR))
+            .unwrap();
+        assert_eq!(cost as usize, grid.hex_distance(&root, &goal).unwrap());
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let grid = Grid::new_square_grid(vec![vec![0, 1, 0]]);
+
+        let walkable = |_: &_, _: &_, cell: &i32| (*cell == 0).then_some(1);
+        assert_eq!(grid.a_star(&(0, 0), &(0, 2), walkable, manhattan), None);
+    }
+
+    #[test]
+    fn overflowing_cost() {
+        let grid = Grid::new_square_grid(vec![vec![1, 0, 0], vec![0, 0, 0]]);
+
+        let steep = |_: &_, _: &_, cell: &i32| Some(if *cell == 1 { u32::MAX } else { 1 });
+        assert_eq!(
+            grid.a_star(&(0, 1), &(0, 0), steep, manhattan),
+            Some((vec![(0, 1), (0, 0)], u32::MAX))
+        );
+        assert_eq!(grid.a_star(&(0, 2), &(0, 0), steep, manhattan), None);
+    }
+}
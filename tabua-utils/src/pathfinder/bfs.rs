@@ -1,18 +1,47 @@
-use crate::board::grid::{Grid, GridBuilder, GridExt, Position2d};
+use std::collections::HashSet;
 
-pub trait BreadthFirstSearch {
-    fn bfs(&self, root: &Position2d, goal: &Position2d) -> Option<Vec<Position2d>>;
+use crate::board::neighbors::Neighbors;
+
+pub trait BreadthFirstSearch: Neighbors {
+    fn bfs(&self, root: &Self::Position, goal: &Self::Position) -> Option<Vec<Self::Position>>;
+
+    /// Shortest path only walking through cells accepted by `passable`
+    fn bfs_with<F>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        passable: F,
+    ) -> Option<Vec<Self::Position>>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool;
+
+    /// Every cell reachable from `root` in at most `steps` moves, with its distance
+    fn reachable_within<F>(
+        &self,
+        root: &Self::Position,
+        steps: usize,
+        passable: F,
+    ) -> Vec<(Self::Position, usize)>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool;
 }
 
-impl<T> BreadthFirstSearch for Grid<T> {
-    fn bfs(&self, root: &Position2d, goal: &Position2d) -> Option<Vec<Position2d>> {
-        let mut explored: Grid<bool> = GridBuilder::new_square_grid()
-            .with_rows(self.row_len())
-            .with_columns(self.column_len())
-            .with_initial_value(false)
-            .build();
+impl<B: Neighbors> BreadthFirstSearch for B {
+    fn bfs(&self, root: &Self::Position, goal: &Self::Position) -> Option<Vec<Self::Position>> {
+        self.bfs_with(root, goal, |_, _| true)
+    }
 
-        *explored.get_mut(root).unwrap() = true;
+    fn bfs_with<F>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        passable: F,
+    ) -> Option<Vec<Self::Position>>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool,
+    {
+        self.cell(root)?;
+        let mut explored = HashSet::from([*root]);
         let mut v = vec![vec![*root]];
         while let Some(path) = v.pop() {
             let p = path.last().unwrap();
@@ -20,9 +49,11 @@ impl<T> BreadthFirstSearch for Grid<T> {
                 return Some(path);
             }
 
-            for (next, _) in self.adjacent_cells(p) {
-                if let Some(false) = explored.get(&next) {
-                    *explored.get_mut(&next).unwrap() = true;
+            for (next, cell) in self.neighbors(p) {
+                if explored.insert(next) {
+                    if !passable(&next, cell) {
+                        continue;
+                    }
 
                     let mut new_path = path.clone();
                     new_path.push(next);
@@ -33,11 +64,43 @@ impl<T> BreadthFirstSearch for Grid<T> {
 
         None
     }
+
+    fn reachable_within<F>(
+        &self,
+        root: &Self::Position,
+        steps: usize,
+        passable: F,
+    ) -> Vec<(Self::Position, usize)>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool,
+    {
+        if self.cell(root).is_none() {
+            return vec![];
+        }
+        let mut explored = HashSet::from([*root]);
+
+        let mut reachable = vec![(*root, 0)];
+        let mut frontier = 0;
+        while let Some((current, distance)) = reachable.get(frontier).copied() {
+            frontier += 1;
+            if distance == steps {
+                continue;
+            }
+
+            for (next, cell) in self.neighbors(&current) {
+                if explored.insert(next) && passable(&next, cell) {
+                    reachable.push((next, distance + 1));
+                }
+            }
+        }
+
+        reachable
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::board::grid::CellAdjacency;
+    use crate::board::grid::{CellAdjacency, Grid, GridBuilder, Topology};
 
     use super::*;
 
@@ -123,4 +186,75 @@ mod tests {
 
         assert_eq!(grid.bfs(&root, &goal), Some(vec![root, (0, 1), goal]))
     }
+
+    #[test]
+    fn hex_goal() {
+        let grid = GridBuilder::new_hex_grid()
+            .with_rows(4)
+            .with_columns(4)
+            .with_initial_value(())
+            .build();
+
+        let root = (0, 0);
+        let goal = (3, 2);
+
+        let path = grid.bfs(&root, &goal).unwrap();
+        assert_eq!(path.len(), grid.hex_distance(&root, &goal).unwrap() + 1);
+        assert_eq!(path.last(), Some(&goal));
+    }
+
+    #[test]
+    fn around_the_wall() {
+        let grid = Grid::new_square_grid(vec![
+            vec!['.', '#', '.'],
+            vec!['.', '#', '.'],
+            vec!['.', '.', '.'],
+        ]);
+
+        let root = (0, 0);
+        let goal = (0, 2);
+
+        assert_eq!(
+            grid.bfs_with(&root, &goal, |_, cell| *cell != '#'),
+            Some(vec![root, (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), goal])
+        )
+    }
+
+    #[test]
+    fn blocked_goal() {
+        let grid = Grid::new_square_grid(vec![vec!['.', '#', '.']]);
+
+        assert_eq!(
+            grid.bfs_with(&(0, 0), &(0, 2), |_, cell| *cell != '#'),
+            None
+        );
+    }
+
+    #[test]
+    fn reachable_within() {
+        let grid = Grid::new_square_grid(vec![
+            vec!['.', '#', '.'],
+            vec!['.', '.', '.'],
+            vec!['.', '.', '.'],
+        ]);
+
+        let mut reachable = grid.reachable_within(&(0, 0), 2, |_, cell| *cell != '#');
+        reachable.sort();
+        assert_eq!(
+            reachable,
+            vec![((0, 0), 0), ((1, 0), 1), ((1, 1), 2), ((2, 0), 2)]
+        );
+    }
+
+    #[test]
+    fn wraps_around() {
+        let grid = GridBuilder::new_square_grid()
+            .with_rows(3)
+            .with_columns(5)
+            .with_initial_value(())
+            .with_topology(Topology::WrapColumns)
+            .build();
+
+        assert_eq!(grid.bfs(&(1, 0), &(1, 4)), Some(vec![(1, 0), (1, 4)]));
+    }
 }
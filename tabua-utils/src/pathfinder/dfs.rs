@@ -1,26 +1,51 @@
-use crate::board::grid::{Grid, GridBuilder, GridExt, Position2d};
-
-pub trait BreadthFirstSearch {
-    fn dfs(&self, root: &Position2d, goal: &Position2d) -> Option<Vec<Position2d>>;
+use std::collections::HashSet;
+
+use crate::board::neighbors::Neighbors;
+
+pub trait DepthFirstSearch: Neighbors {
+    fn dfs(&self, root: &Self::Position, goal: &Self::Position) -> Option<Vec<Self::Position>>;
+
+    /// Any path only walking through cells accepted by `passable`
+    fn dfs_with<F>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        passable: F,
+    ) -> Option<Vec<Self::Position>>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool;
 }
 
-impl<T> BreadthFirstSearch for Grid<T> {
-    fn dfs(&self, root: &Position2d, goal: &Position2d) -> Option<Vec<Position2d>> {
-        let mut explored: Grid<bool> = GridBuilder::new_square_grid()
-            .with_rows(self.row_len())
-            .with_columns(self.column_len())
-            .with_initial_value(false)
-            .build();
+impl<B: Neighbors> DepthFirstSearch for B {
+    fn dfs(&self, root: &Self::Position, goal: &Self::Position) -> Option<Vec<Self::Position>> {
+        self.dfs_with(root, goal, |_, _| true)
+    }
+
+    fn dfs_with<F>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        passable: F,
+    ) -> Option<Vec<Self::Position>>
+    where
+        F: Fn(&Self::Position, &Self::Cell) -> bool,
+    {
+        self.cell(root)?;
+        let mut explored = HashSet::new();
 
         let mut stack = vec![(*root, vec![*root])];
         while let Some((current, path)) = stack.pop() {
-            if let Some(false) = explored.get(&current) {
+            if !explored.contains(&current) {
                 if current == *goal {
                     return Some(path);
                 }
-                *explored.get_mut(&current).unwrap() = true;
-                for (next, _) in self.adjacent_cells(&current) {
-                    if let Some(false) = explored.get(&next) {
+                explored.insert(current);
+                for (next, cell) in self.neighbors(&current) {
+                    if !explored.contains(&next) {
+                        if !passable(&next, cell) {
+                            continue;
+                        }
+
                         let mut new_path = path.clone();
                         new_path.push(next);
                         stack.push((next, new_path));
@@ -35,7 +60,7 @@ impl<T> BreadthFirstSearch for Grid<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::board::grid::CellAdjacency;
+    use crate::board::grid::{CellAdjacency, Grid, GridBuilder};
 
     use super::*;
 
@@ -118,4 +143,28 @@ mod tests {
 
         assert!(grid.dfs(&root, &goal).is_some())
     }
+
+    #[test]
+    fn around_the_wall() {
+        let grid = Grid::new_square_grid(vec![
+            vec!['.', '#', '.'],
+            vec!['.', '#', '.'],
+            vec!['.', '.', '.'],
+        ]);
+
+        let path = grid
+            .dfs_with(&(0, 0), &(0, 2), |_, cell| *cell != '#')
+            .unwrap();
+        assert!(!path.contains(&(0, 1)) && !path.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn blocked_goal() {
+        let grid = Grid::new_square_grid(vec![vec!['.', '#', '.']]);
+
+        assert_eq!(
+            grid.dfs_with(&(0, 0), &(0, 2), |_, cell| *cell != '#'),
+            None
+        );
+    }
 }
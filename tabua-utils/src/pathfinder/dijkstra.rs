@@ -0,0 +1,83 @@
+use crate::board::neighbors::Neighbors;
+
+use super::astar::AStar;
+
+pub trait Dijkstra: Neighbors {
+    /// Cheapest path from `root` to `goal` and its total cost.
+    ///
+    /// `cost` gives the price of moving between two adjacent cells, receiving
+    /// the destination cell, or `None` when the move is impossible.
+    fn dijkstra<C>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        cost: C,
+    ) -> Option<(Vec<Self::Position>, u32)>
+    where
+        C: Fn(&Self::Position, &Self::Position, &Self::Cell) -> Option<u32>;
+}
+
+impl<B: Neighbors> Dijkstra for B {
+    fn dijkstra<C>(
+        &self,
+        root: &Self::Position,
+        goal: &Self::Position,
+        cost: C,
+    ) -> Option<(Vec<Self::Position>, u32)>
+    where
+        C: Fn(&Self::Position, &Self::Position, &Self::Cell) -> Option<u32>,
+    {
+        self.a_star(root, goal, cost, |_, _| 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::grid::Grid;
+
+    #[test]
+    fn adjacent_goal() {
+        let grid = Grid::new_square_grid(vec![vec![1, 1], vec![1, 1]]);
+
+        let root = (0, 0);
+        let goal = (0, 1);
+
+        assert_eq!(
+            grid.dijkstra(&root, &goal, |_, _, cost| Some(*cost)),
+            Some((vec![root, goal], 1))
+        );
+    }
+
+    #[test]
+    fn avoid_expensive_terrain() {
+        let grid = Grid::new_square_grid(vec![vec![1, 9, 1], vec![1, 9, 1], vec![1, 1, 1]]);
+
+        let root = (0, 0);
+        let goal = (0, 2);
+
+        assert_eq!(
+            grid.dijkstra(&root, &goal, |_, _, cost| Some(*cost)),
+            Some((vec![root, (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), goal], 6))
+        );
+    }
+
+    #[test]
+    fn cross_expensive_terrain_when_cheaper() {
+        let grid = Grid::new_square_grid(vec![vec![1, 3, 1], vec![1, 9, 1], vec![1, 1, 1]]);
+
+        let (path, cost) = grid
+            .dijkstra(&(0, 0), &(0, 2), |_, _, cost| Some(*cost))
+            .unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path, vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn impassable_cells() {
+        let grid = Grid::new_square_grid(vec![vec![1, 0, 1], vec![1, 0, 1]]);
+
+        let passable = |_: &_, _: &_, cost: &u32| (*cost > 0).then_some(*cost);
+        assert_eq!(grid.dijkstra(&(0, 0), &(0, 2), passable), None);
+    }
+}
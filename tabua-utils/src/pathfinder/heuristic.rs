@@ -0,0 +1,63 @@
+//! Distance estimates for [`AStar`](super::astar::AStar).
+//!
+//! They never overestimate as long as every step costs at least 1.
+
+use crate::board::grid::{Position2d, Topology};
+use crate::board::hex::{HexCoord, HexLayout};
+
+/// Square grids with `CellAdjacency::Side` and [`Topology::Bounded`], it
+/// overestimates across wrapped edges, see [`wrapped_manhattan`]
+pub fn manhattan(from: &Position2d, to: &Position2d) -> u32 {
+    (from.0.abs_diff(to.0) + from.1.abs_diff(to.1)) as u32
+}
+
+/// Square grids with `CellAdjacency::SideAndVertex` and [`Topology::Bounded`],
+/// it overestimates across wrapped edges, see [`wrapped_chebyshev`]
+pub fn chebyshev(from: &Position2d, to: &Position2d) -> u32 {
+    from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)) as u32
+}
+
+/// Steps between two rows or columns, going around the seam when the axis wraps
+fn axis_distance(from: usize, to: usize, len: usize, wraps: bool) -> usize {
+    let distance = from.abs_diff(to);
+    if wraps {
+        distance.min(len.saturating_sub(distance))
+    } else {
+        distance
+    }
+}
+
+/// [`manhattan`] for `rows` x `columns` square grids with any topology
+pub fn wrapped_manhattan(
+    rows: usize,
+    columns: usize,
+    topology: Topology,
+) -> impl Fn(&Position2d, &Position2d) -> u32 {
+    move |from, to| {
+        (axis_distance(from.0, to.0, rows, topology.wraps_rows())
+            + axis_distance(from.1, to.1, columns, topology.wraps_columns())) as u32
+    }
+}
+
+/// [`chebyshev`] for `rows` x `columns` square grids with any topology
+pub fn wrapped_chebyshev(
+    rows: usize,
+    columns: usize,
+    topology: Topology,
+) -> impl Fn(&Position2d, &Position2d) -> u32 {
+    move |from, to| {
+        axis_distance(from.0, to.0, rows, topology.wraps_rows()).max(axis_distance(
+            from.1,
+            to.1,
+            columns,
+            topology.wraps_columns(),
+        )) as u32
+    }
+}
+
+/// Hex grids laid out with `layout`
+pub fn hex_distance(layout: HexLayout) -> impl Fn(&Position2d, &Position2d) -> u32 {
+    move |from, to| {
+        HexCoord::from_offset(from, layout).distance(&HexCoord::from_offset(to, layout)) as u32
+    }
+}
@@ -1,2 +1,5 @@
+pub mod astar;
 pub mod bfs;
 pub mod dfs;
+pub mod dijkstra;
+pub mod heuristic;
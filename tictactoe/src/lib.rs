@@ -5,10 +5,18 @@ use std::fmt::Display;
 use async_trait::async_trait;
 use error::{Error, Result};
 use serde::{Deserialize, Serialize};
-use tabua_utils::board::grid::{Grid, GridBuilder, GridExt};
+use tabua_utils::board::grid::{Grid, GridBuilder, GridExt, GridExtMut, Position2dDiff};
+use tabua_utils::board::line::{Line, LineDetection};
 
 pub mod error;
 
+/// Rows, columns, diagonals and anti-diagonals
+const LINE_DIRECTIONS: [Position2dDiff; 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Bound of [`TicTacToeState::heuristic`], a quarter of the win score of the
+/// `tabua-ai` minimax so positions are never mistaken for won games
+pub const MAX_HEURISTIC: i64 = 1 << 38;
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CellState {
     Empty,
@@ -78,6 +86,58 @@ impl TicTacToeState {
     pub fn current_player(&self) -> PlayerId {
         self.current_player
     }
+
+    /// Heuristic score of the position for `player`.
+    ///
+    /// Every window of `required_sequence_length` cells that can still be
+    /// completed by a single player is worth more the more marks it holds.
+    /// The score stays within [`MAX_HEURISTIC`] either way.
+    pub fn heuristic(&self, player: PlayerId) -> i64 {
+        let board = &self.board;
+        let length = self.required_sequence_length as i32;
+        let rows = board.row_len() as i32;
+        let columns = board.column_len() as i32;
+        let mut score = 0_i64;
+
+        for row in 0..rows {
+            for column in 0..columns {
+                for (d_row, d_column) in LINE_DIRECTIONS {
+                    let end_row = row + d_row * (length - 1);
+                    let end_column = column + d_column * (length - 1);
+                    if end_row >= rows || end_column < 0 || end_column >= columns {
+                        continue;
+                    }
+
+                    let mut own = 0;
+                    let mut opponent = 0;
+                    for step in 0..length {
+                        let cell = (
+                            (row + d_row * step) as usize,
+                            (column + d_column * step) as usize,
+                        );
+                        match board.get(&cell) {
+                            Some(mark) if *mark == player.mark() => own += 1,
+                            Some(CellState::Empty) | None => {}
+                            Some(_) => opponent += 1,
+                        }
+                    }
+
+                    match (own, opponent) {
+                        // long k-in-a-row lines would overflow, they are clamped below
+                        (own, 0) if own > 0 => {
+                            score = score.saturating_add(10_i64.saturating_pow(own))
+                        }
+                        (0, opponent) if opponent > 0 => {
+                            score = score.saturating_sub(10_i64.saturating_pow(opponent))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        score.clamp(-MAX_HEURISTIC, MAX_HEURISTIC)
+    }
 }
 
 impl Default for TicTacToeState {
@@ -92,6 +152,28 @@ pub enum PlayerId {
     Cross,
 }
 
+impl PlayerId {
+    pub fn opponent(&self) -> PlayerId {
+        match self {
+            PlayerId::Circle => PlayerId::Cross,
+            PlayerId::Cross => PlayerId::Circle,
+        }
+    }
+
+    pub fn mark(&self) -> CellState {
+        match self {
+            PlayerId::Circle => CellState::Circle,
+            PlayerId::Cross => CellState::Cross,
+        }
+    }
+}
+
+impl tabua_engine::ActivePlayers<PlayerId> for PlayerId {
+    fn active_players(self) -> Vec<PlayerId> {
+        vec![self]
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct Position {
     pub row: usize,
@@ -118,86 +200,66 @@ impl TicTacToeEngine {
         Self { state }
     }
 
-    fn player_result(&self, player_id: PlayerId) -> EndGameState {
-        let player_mark = match player_id {
-            PlayerId::Circle => CellState::Circle,
-            PlayerId::Cross => CellState::Cross,
-        };
-
-        let board = &self.state.board;
-        let board_size = board.row_len();
-        let required_sequence_length = self.state.required_sequence_length;
+    fn player_line(&self, player_id: PlayerId) -> Option<Line> {
+        self.state
+            .board
+            .lines_along(
+                &LINE_DIRECTIONS,
+                self.state.required_sequence_length,
+                |cell| *cell == player_id.mark(),
+            )
+            .into_iter()
+            .next()
+    }
 
-        // Check victory condition: row
-        for rows in board.rows() {
-            for window in rows.windows(required_sequence_length) {
-                if window.iter().all(|x| *x == player_mark) {
-                    return EndGameState::Winner(player_id);
-                }
-            }
+    fn player_result(&self, player_id: PlayerId) -> EndGameState {
+        match self.player_line(player_id) {
+            Some(_) => EndGameState::Winner(player_id),
+            None => EndGameState::GameNotOver,
         }
+    }
 
-        // Check victory condition: column
-        for col in 0..board_size {
-            for start_col in 0..(board_size - required_sequence_length + 1) {
-                let window: Vec<_> = board
-                    .iter_column(col)
-                    .skip(start_col)
-                    .take(required_sequence_length)
-                    .collect();
-                if window.iter().all(|x| **x == player_mark)
-                    && window.len() == required_sequence_length
-                {
-                    return EndGameState::Winner(player_id);
-                }
+    /// Same as [`tabua_engine::Engine::results`], without awaiting
+    fn end_game_state(&self) -> EndGameState {
+        let result = match (
+            self.player_result(PlayerId::Cross),
+            self.player_result(PlayerId::Circle),
+        ) {
+            (EndGameState::GameNotOver, EndGameState::Winner(player_id))
+            | (EndGameState::Winner(player_id), EndGameState::GameNotOver) => {
+                EndGameState::Winner(player_id)
             }
-        }
-
-        // Check victory condition: diagonals
-        for r in 0..board.row_len() {
-            for c in 0..board.column_len() {
-                let mut count = 0;
-                for (row, col) in (r..board.row_len()).zip(c..board.column_len()) {
-                    if let Some(mark) = board.get(&(row, col)) {
-                        if *mark == player_mark {
-                            count += 1;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
-
-                if count == required_sequence_length {
-                    return EndGameState::Winner(player_id);
-                }
-
-                let mut count = 0;
-                for (row, col) in (0..=r).zip((0..=c).rev()) {
-                    if let Some(mark) = board.get(&(row, col)) {
-                        if *mark == player_mark {
-                            count += 1;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    }
-                }
-
-                if count == required_sequence_length {
-                    return EndGameState::Winner(player_id);
-                }
+            (EndGameState::GameNotOver, _) | (_, EndGameState::GameNotOver) => {
+                EndGameState::GameNotOver
             }
+            _ => unreachable!(),
+        };
+
+        if !matches!(result, EndGameState::Winner(_)) && !self.has_empty_cell() {
+            return EndGameState::Tie;
         }
 
-        EndGameState::GameNotOver
+        result
+    }
+
+    /// Cells of the sequence that won the game, `None` while there is no winner
+    pub fn winning_line(&self) -> Option<Vec<Position>> {
+        let EndGameState::Winner(winner) = self.end_game_state() else {
+            return None;
+        };
+        let line = self.player_line(winner)?;
+
+        Some(
+            line.cells
+                .into_iter()
+                .map(|(row, column)| Position::new(row, column))
+                .collect(),
+        )
     }
 
     pub fn has_empty_cell(&self) -> bool {
         for row in self.state.board.rows() {
-            if row.iter().any(|x| *x == CellState::Empty) {
+            if row.contains(&CellState::Empty) {
                 return true;
             }
         }
@@ -213,6 +275,20 @@ pub enum EndGameState {
     Winner(PlayerId),
 }
 
+impl tabua_engine::Outcome<PlayerId> for EndGameState {
+    fn is_over(&self) -> bool {
+        *self != EndGameState::GameNotOver
+    }
+
+    fn reward(&self, player: &PlayerId) -> f64 {
+        match self {
+            EndGameState::Winner(winner) if winner == player => 1.0,
+            EndGameState::Winner(_) => 0.0,
+            EndGameState::Tie | EndGameState::GameNotOver => 0.5,
+        }
+    }
+}
+
 #[async_trait]
 impl tabua_engine::Engine<'_> for TicTacToeEngine {
     type PublicState = TicTacToeState;
@@ -262,6 +338,27 @@ impl tabua_engine::Engine<'_> for TicTacToeEngine {
 
         Ok(())
     }
+    async fn legal_actions(&self, player: &Self::PlayerId) -> Result<Vec<Self::Action>> {
+        let state = &self.state;
+
+        if state.current_player != *player || self.results().await? != EndGameState::GameNotOver {
+            return Ok(vec![]);
+        }
+
+        let mut actions = vec![];
+        for (row, cells) in state.board.rows().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if *cell == CellState::Empty {
+                    actions.push(Action::MarkBoard {
+                        player_id: *player,
+                        pos: Position::new(row, column),
+                    });
+                }
+            }
+        }
+
+        Ok(actions)
+    }
     async fn apply_action(&mut self, action: Action) -> Result<()> {
         self.validate_action(&action).await?;
 
@@ -272,16 +369,8 @@ impl tabua_engine::Engine<'_> for TicTacToeEngine {
             } => {
                 let state = &mut self.state;
 
-                match player_id {
-                    PlayerId::Circle => {
-                        *state.board.get_mut(&(row, column)).unwrap() = CellState::Circle;
-                        self.state.current_player = PlayerId::Cross;
-                    }
-                    PlayerId::Cross => {
-                        *state.board.get_mut(&(row, column)).unwrap() = CellState::Cross;
-                        self.state.current_player = PlayerId::Circle;
-                    }
-                }
+                *state.board.get_mut(&(row, column)).unwrap() = player_id.mark();
+                state.current_player = player_id.opponent();
 
                 Ok(())
             }
@@ -293,31 +382,20 @@ impl tabua_engine::Engine<'_> for TicTacToeEngine {
     }
 
     async fn results(&self) -> Result<Self::EndGame> {
-        let result = match (
-            self.player_result(PlayerId::Cross),
-            self.player_result(PlayerId::Circle),
-        ) {
-            (EndGameState::GameNotOver, EndGameState::Winner(player_id))
-            | (EndGameState::Winner(player_id), EndGameState::GameNotOver) => {
-                EndGameState::Winner(player_id)
-            }
-            (EndGameState::GameNotOver, _) | (_, EndGameState::GameNotOver) => {
-                EndGameState::GameNotOver
-            }
-            _ => unreachable!(),
-        };
-
-        if !matches!(result, EndGameState::Winner(_)) && !self.has_empty_cell() {
-            return Ok(EndGameState::Tie);
-        }
+        Ok(self.end_game_state())
+    }
+}
 
-        Ok(result)
+impl tabua_engine::FromPublicState<'_> for TicTacToeEngine {
+    fn from_public_state(state: Self::PublicState) -> Self {
+        Self::new(state)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use tabua_engine::Engine;
+    use serde_json::json;
+    use tabua_engine::{game_log::GameLog, Engine};
 
     use super::*;
 
@@ -418,6 +496,130 @@ mod tests {
             .is_err());
     }
 
+    #[tokio::test]
+    async fn legal_actions_initial_state() {
+        let engine = TicTacToeEngine::new(TicTacToeState::default());
+        let actions = engine.legal_actions(&PlayerId::Cross).await.unwrap();
+
+        assert_eq!(actions.len(), 9);
+        for action in actions {
+            assert!(engine.validate_action(&action).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn legal_actions_wrong_player() {
+        let engine = TicTacToeEngine::new(TicTacToeState::default());
+        assert!(engine
+            .legal_actions(&PlayerId::Circle)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn legal_actions_skip_marked_cells() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        engine
+            .apply_action(Action::MarkBoard {
+                player_id: PlayerId::Cross,
+                pos: Position::new(1, 1),
+            })
+            .await
+            .unwrap();
+
+        let actions = engine.legal_actions(&PlayerId::Circle).await.unwrap();
+        assert_eq!(actions.len(), 8);
+        assert!(!actions.contains(&Action::MarkBoard {
+            player_id: PlayerId::Circle,
+            pos: Position::new(1, 1),
+        }));
+    }
+
+    #[tokio::test]
+    async fn legal_actions_game_over() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        engine.state.board = Grid::new_square_grid(vec![
+            vec![CellState::Cross, CellState::Cross, CellState::Cross],
+            vec![CellState::Circle, CellState::Circle, CellState::Empty],
+            vec![CellState::Empty, CellState::Empty, CellState::Empty],
+        ]);
+        engine.state.current_player = PlayerId::Circle;
+
+        assert!(engine
+            .legal_actions(&PlayerId::Circle)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_game_log() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        let mut log = GameLog::new(TicTacToeState::default());
+        for (player_id, pos) in [
+            (PlayerId::Cross, Position::new(0, 0)),
+            (PlayerId::Circle, Position::new(1, 1)),
+            (PlayerId::Cross, Position::new(0, 1)),
+        ] {
+            log.apply(&mut engine, player_id, Action::MarkBoard { player_id, pos })
+                .await
+                .unwrap();
+        }
+
+        let replayed: TicTacToeEngine = log.replay().await.unwrap();
+        assert_eq!(replayed.state, engine.state);
+
+        let replayed: TicTacToeEngine = log.replay_until(1).await.unwrap();
+        assert_eq!(replayed.state.current_player, PlayerId::Circle);
+        assert_eq!(replayed.state.board.get(&(0, 0)), Some(&CellState::Cross));
+        assert_eq!(replayed.state.board.get(&(1, 1)), Some(&CellState::Empty));
+    }
+
+    #[tokio::test]
+    async fn dyn_engine() {
+        let mut engine: Box<dyn tabua_engine::dyn_engine::DynEngine> =
+            Box::new(TicTacToeEngine::new(TicTacToeState::default()));
+
+        engine
+            .apply_action(json!({
+                "MarkBoard": { "player_id": "Cross", "pos": { "row": 0, "column": 0 } }
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(engine.current_players().await.unwrap(), json!("Circle"));
+        assert_eq!(engine.results().await.unwrap(), json!("GameNotOver"));
+        assert_eq!(
+            engine.legal_actions(json!("Circle")).await.unwrap()[0],
+            json!({ "MarkBoard": { "player_id": "Circle", "pos": { "row": 0, "column": 1 } } })
+        );
+    }
+
+    #[test]
+    fn heuristic() {
+        let mut state = TicTacToeState::default();
+        assert_eq!(state.heuristic(PlayerId::Cross), 0);
+
+        *state.board.get_mut(&(1, 1)).unwrap() = CellState::Cross;
+        assert!(state.heuristic(PlayerId::Cross) > 0);
+        assert_eq!(
+            state.heuristic(PlayerId::Circle),
+            -state.heuristic(PlayerId::Cross)
+        );
+    }
+
+    #[test]
+    fn heuristic_long_lines() {
+        let mut state = TicTacToeState::new(20, 20).unwrap();
+        for column in 0..20 {
+            *state.board.get_mut(&(0, column)).unwrap() = CellState::Cross;
+        }
+
+        assert_eq!(state.heuristic(PlayerId::Cross), MAX_HEURISTIC);
+        assert_eq!(state.heuristic(PlayerId::Circle), -MAX_HEURISTIC);
+    }
+
     #[tokio::test]
     async fn game_not_over() {
         let engine = TicTacToeEngine::new(TicTacToeState::default());
@@ -514,6 +716,70 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn winning_line_row() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        assert_eq!(engine.winning_line(), None);
+        engine.state.board = Grid::new_square_grid(vec![
+            vec![CellState::Empty, CellState::Empty, CellState::Empty],
+            vec![CellState::Circle, CellState::Circle, CellState::Circle],
+            vec![CellState::Cross, CellState::Empty, CellState::Cross],
+        ]);
+        assert_eq!(
+            engine.winning_line(),
+            Some(vec![
+                Position::new(1, 0),
+                Position::new(1, 1),
+                Position::new(1, 2)
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn winning_line_anti_diagonal() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        engine.state.board = Grid::new_square_grid(vec![
+            vec![CellState::Circle, CellState::Circle, CellState::Cross],
+            vec![CellState::Empty, CellState::Cross, CellState::Empty],
+            vec![CellState::Cross, CellState::Empty, CellState::Circle],
+        ]);
+        assert_eq!(
+            engine.winning_line(),
+            Some(vec![
+                Position::new(0, 2),
+                Position::new(1, 1),
+                Position::new(2, 0)
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn winning_line_longer_than_required() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::new(4, 3).unwrap());
+        *engine.state.board.get_mut(&(0, 0)).unwrap() = CellState::Cross;
+        for row in 0..4 {
+            *engine.state.board.get_mut(&(row, 1)).unwrap() = CellState::Circle;
+        }
+
+        assert_eq!(
+            engine.results().await.unwrap(),
+            EndGameState::Winner(PlayerId::Circle)
+        );
+        assert_eq!(engine.winning_line().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn no_winning_line_on_tie() {
+        let mut engine = TicTacToeEngine::new(TicTacToeState::default());
+        engine.state.board = Grid::new_square_grid(vec![
+            vec![CellState::Circle, CellState::Cross, CellState::Circle],
+            vec![CellState::Cross, CellState::Cross, CellState::Circle],
+            vec![CellState::Circle, CellState::Circle, CellState::Cross],
+        ]);
+        assert_eq!(engine.results().await.unwrap(), EndGameState::Tie);
+        assert_eq!(engine.winning_line(), None);
+    }
+
     #[tokio::test]
     async fn no_moves_available() {
         let mut engine = TicTacToeEngine::new(TicTacToeState::default());